@@ -0,0 +1,46 @@
+//! Runs `protocol::ObjectStatus::decode` (object id, position and stats) over arbitrary buffers
+//! and checks that whatever decodes successfully is written back the same way every time.
+
+#![no_main]
+
+use bytes::BytesMut;
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code, unused_imports, unused_macros)]
+#[path = "../../src"]
+mod src {
+	pub mod protocol;
+	pub mod util;
+}
+use src::{protocol, util};
+
+// needed by util::packet_ids
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Direction {
+	C2S,
+	S2C,
+}
+
+fuzz_target!(|data: &[u8]| {
+	let b = BytesMut::from(data);
+	let c = &mut 0;
+	let Ok(status) = protocol::ObjectStatus::decode(&b, c) else {
+		return;
+	};
+	assert!(*c <= b.len());
+
+	let mut out = BytesMut::new();
+	status.encode(&mut out);
+
+	let c = &mut 0;
+	let decoded = protocol::ObjectStatus::decode(&out, c).expect("encoded object data decodes");
+	assert_eq!(*c, out.len());
+	assert_eq!(decoded.object_id, status.object_id);
+	assert_eq!(decoded.stats.len(), status.stats.len());
+
+	// comparing bytes because positions may be NaN
+	let mut reencoded = BytesMut::new();
+	decoded.encode(&mut reencoded);
+	assert_eq!(out, reencoded);
+});
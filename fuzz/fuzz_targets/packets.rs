@@ -0,0 +1,51 @@
+//! Decodes arbitrary buffers as client and server packets and checks that whatever
+//! decodes successfully is written back the same way every time.
+
+#![no_main]
+
+use bytes::{Bytes, BytesMut};
+use libfuzzer_sys::fuzz_target;
+use protocol::{C2SPacket, S2CPacket};
+
+#[allow(dead_code, unused_imports, unused_macros)]
+#[path = "../../src"]
+mod src {
+	pub mod protocol;
+	pub mod util;
+}
+use src::{protocol, util};
+
+// needed by util::packet_ids
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Direction {
+	C2S,
+	S2C,
+}
+
+// decodes a whole packet (with the id) and encodes it again
+fn reencode(direction: Direction, data: &[u8]) -> Option<Bytes> {
+	let b = BytesMut::from(data);
+	let (&packet_id, _) = data.split_first()?;
+	let c = &mut 1;
+
+	let bytes = match direction {
+		Direction::C2S => C2SPacket::decode(packet_id, &b, c).ok()??.to_bytes(),
+		Direction::S2C => S2CPacket::decode(packet_id, &b, c).ok()??.to_bytes(),
+	};
+	assert!(*c <= b.len());
+
+	Some(bytes)
+}
+
+fuzz_target!(|data: &[u8]| {
+	for direction in [Direction::C2S, Direction::S2C] {
+		let Some(encoded) = reencode(direction, data) else {
+			continue;
+		};
+
+		// comparing bytes because floats may be NaN
+		let reencoded = reencode(direction, &encoded).expect("encoded packet decodes");
+		assert_eq!(encoded, reencoded);
+	}
+});
@@ -40,6 +40,47 @@ pub struct ProjectileInfo {
 	pub damage: Either<i32, (i32, i32)>,
 	pub armor_piercing: bool,
 	pub inflicts: Vec<ProjectileCondition>,
+	/// Can hit more than one target
+	pub multi_hit: bool,
+	/// Flies through walls and other obstacles
+	pub passes_cover: bool,
+	/// How big it's drawn, in percent
+	pub size: u32,
+	pub movement: Movement,
+}
+
+/// Everything that decides where a projectile goes
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Movement {
+	/// tiles per 10 seconds
+	pub speed: f32,
+	pub lifetime_ms: u32,
+	/// How much the speed changes per second, in the same units as speed
+	pub acceleration: f32,
+	/// How long it flies at the starting speed before accelerating
+	pub acceleration_delay_ms: u32,
+	/// The speed doesn't go past this when accelerating
+	pub speed_clamp: Option<f32>,
+	pub path: Path,
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum Path {
+	Straight {
+		/// Comes back after flying half of its lifetime
+		boomerang: bool,
+		/// Sideways deflection, in tiles
+		amplitude: f32,
+		/// Deflection periods per lifetime
+		frequency: f32,
+	},
+	/// Flies in a wave, with a fixed amplitude and frequency
+	Wavy,
+	/// Flies in a figure-eight around the starting point
+	Parametric {
+		/// Size of the figure, in tiles
+		magnitude: f32,
+	},
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Default)]
@@ -96,6 +137,23 @@ impl Assets {
 	}
 }
 
+#[cfg(test)]
+impl Assets {
+	/// Assets without anything in them, so tests can fill in only what they need
+	pub fn empty() -> Self {
+		Self {
+			sprites: Sprites {
+				animated_spritesheets: HashMap::new(),
+				spritesheets: HashMap::new(),
+			},
+			objects: HashMap::new(),
+			enchantments: HashMap::new(),
+			tiles: HashMap::new(),
+			reverse_changes_guard: None,
+		}
+	}
+}
+
 pub fn handle_assets(config: &Config) -> anyhow::Result<Assets> {
 	if config.assets_res.as_os_str().is_empty() {
 		bail!("assets_res not set. Please edit your rotmguard.toml!",);
@@ -1,12 +1,16 @@
 use super::{XMLUtility, parse_id};
 use crate::{
-	assets::{Object, ProjectileCondition, ProjectileInfo, SpriteId},
+	assets::{Movement, Object, Path, ProjectileCondition, ProjectileInfo, SpriteId},
 	config::Config,
-	util::{CONDITION_BITFLAG, CONDITION2_BITFLAG},
+	util::ConditionEffect,
 };
 use anyhow::{Context, Result, bail};
 use either::Either;
-use std::collections::{BTreeMap, HashMap};
+use std::{
+	collections::{BTreeMap, HashMap},
+	str::FromStr,
+};
+use tracing::{debug, warn};
 use xmltree::{Element, XMLNode};
 
 // returns true if any modifications were made
@@ -75,7 +79,7 @@ fn parse_object(
 	for parameter in object.child_elements() {
 		match parameter.name.as_str() {
 			"Projectile" => {
-				let (modded, id, projectile) = parse_projectile(config, parameter)
+				let (modded, id, projectile) = parse_projectile(config, &name, parameter)
 					.with_context(|| format!("projectile {projectile_i}"))?;
 
 				modified |= modded;
@@ -123,8 +127,10 @@ fn parse_object(
 	Ok(modified)
 }
 
+// the object name is only for warnings
 fn parse_projectile(
 	config: &Config,
+	object: &str,
 	projectile: &mut Element,
 ) -> Result<(bool, Option<u8>, ProjectileInfo)> {
 	let mut modified = false;
@@ -147,7 +153,12 @@ fn parse_projectile(
 		_ => bail!("invalid damage"),
 	};
 
+	let size = child_or(object, projectile, "Size", 100);
+	let movement = parse_movement(object, projectile);
+
 	let mut armor_piercing = false;
+	let mut multi_hit = false;
+	let mut passes_cover = false;
 	let mut inflicts = Vec::new();
 
 	// iterating using indexes instead of directly, because we will modify as we go (removing debuffs)
@@ -162,6 +173,12 @@ fn parse_projectile(
 			"ArmorPiercing" => {
 				armor_piercing = true;
 			}
+			"MultiHit" => {
+				multi_hit = true;
+			}
+			"PassesCover" => {
+				passes_cover = true;
+			}
 			"ConditionEffect" => {
 				let condition_name = &*projectile_parameter.get_text().with_context(|| {
 					format!("parameter {projectile_parameter_i} ConditionEffect text")
@@ -179,7 +196,7 @@ fn parse_projectile(
 
 				// Client-side debuffs for force antidebuff
 				if config.settings.edit_assets.force_debuffs {
-					let debuffs = &config.settings.debuffs;
+					let debuffs = *config.settings.debuffs.lock().unwrap();
 					let c = condition_name;
 					if (c == "Blind" && debuffs.blind)
 						|| (c == "Hallucinating" && debuffs.hallucinating)
@@ -199,26 +216,11 @@ fn parse_projectile(
 					.parse()
 					.with_context(|| format!("parameter {projectile_parameter_i} duration"))?;
 
-				match condition_name {
-					"Curse" => {
-						condition.condition2 |= CONDITION2_BITFLAG::CURSED;
-					}
-					"Exposed" => {
-						condition.condition2 |= CONDITION2_BITFLAG::EXPOSED;
-					}
-					"Sick" => {
-						condition.condition |= CONDITION_BITFLAG::SICK;
-					}
-					"Bleeding" => {
-						condition.condition |= CONDITION_BITFLAG::BLEEDING;
-					}
-					"Armor Broken" => {
-						condition.condition |= CONDITION_BITFLAG::ARMOR_BROKEN;
+				match ConditionEffect::by_name(condition_name) {
+					Some(effect) => {
+						(condition.condition, condition.condition2) = effect.bitflags();
 					}
-					"Weak" => {
-						condition.condition |= CONDITION_BITFLAG::WEAK;
-					}
-					_ => {} // may want to handle more later
+					None => debug!("unknown condition effect {condition_name}"),
 				}
 
 				inflicts.push(condition);
@@ -234,10 +236,57 @@ fn parse_projectile(
 			damage,
 			armor_piercing,
 			inflicts,
+			multi_hit,
+			passes_cover,
+			size,
+			movement,
 		},
 	))
 }
 
+// Only used for simulating bullets, so anything invalid here is just warned about
+// instead of failing all of the assets
+fn parse_movement(object: &str, projectile: &Element) -> Movement {
+	let path = if projectile.get_child("Wavy").is_some() {
+		Path::Wavy
+	} else if projectile.get_child("Parametric").is_some() {
+		Path::Parametric {
+			magnitude: child_or(object, projectile, "Magnitude", 3.0),
+		}
+	} else {
+		Path::Straight {
+			boomerang: projectile.get_child("Boomerang").is_some(),
+			amplitude: child_or(object, projectile, "Amplitude", 0.0),
+			frequency: child_or(object, projectile, "Frequency", 1.0),
+		}
+	};
+
+	Movement {
+		speed: child_or(object, projectile, "Speed", 0.0),
+		lifetime_ms: child_or::<f32>(object, projectile, "LifetimeMS", 0.0) as u32,
+		acceleration: child_or(object, projectile, "Acceleration", 0.0),
+		acceleration_delay_ms: child_or::<f32>(object, projectile, "AccelerationDelay", 0.0) as u32,
+		speed_clamp: optional_child(object, projectile, "SpeedClamp"),
+		path,
+	}
+}
+
+// parses the text of an optional child element, None if it's not there or invalid
+fn optional_child<T: FromStr>(object: &str, element: &Element, name: &str) -> Option<T> {
+	let text = element.get_child_text(name)?;
+	match text.trim().parse() {
+		Ok(value) => Some(value),
+		Err(_) => {
+			warn!("{object}: invalid projectile {name} {text:?}, using the default");
+			None
+		}
+	}
+}
+
+fn child_or<T: FromStr>(object: &str, element: &Element, name: &str, default: T) -> T {
+	optional_child(object, element, name).unwrap_or(default)
+}
+
 fn parse_subattack(subattack: &mut Element) -> Result<u8> {
 	let projectile_id = subattack
 		.attributes
@@ -248,3 +297,64 @@ fn parse_subattack(subattack: &mut Element) -> Result<u8> {
 
 	Ok(projectile_id)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn movement() {
+		let projectile = Element::parse(
+			&b"<Projectile><Speed>120</Speed><LifetimeMS>1500.5</LifetimeMS><Boomerang/>\
+			<Amplitude>0.4</Amplitude><Acceleration>-20</Acceleration><SpeedClamp>40</SpeedClamp>\
+			</Projectile>"[..],
+		)
+		.unwrap();
+
+		assert_eq!(
+			parse_movement("Test", &projectile),
+			Movement {
+				speed: 120.0,
+				lifetime_ms: 1500,
+				acceleration: -20.0,
+				acceleration_delay_ms: 0,
+				speed_clamp: Some(40.0),
+				path: Path::Straight {
+					boomerang: true,
+					amplitude: 0.4,
+					frequency: 1.0,
+				},
+			}
+		);
+
+		// invalid values are left at the default
+		let projectile = Element::parse(
+			&b"<Projectile><Parametric/><Speed>abc</Speed><LifetimeMS>800</LifetimeMS>\
+			<SpeedClamp>fast</SpeedClamp></Projectile>"[..],
+		)
+		.unwrap();
+		let movement = parse_movement("Test", &projectile);
+		assert_eq!(movement.speed, 0.0);
+		assert_eq!(movement.lifetime_ms, 800);
+		assert_eq!(movement.speed_clamp, None);
+	}
+
+	#[test]
+	fn malformed_projectile() {
+		let mut xml = Element::parse(
+			&br#"<Objects><Object type="0x1234" id="Test">
+			<Projectile><Damage>50</Damage><Size>1.5</Size><Speed>12O</Speed></Projectile>
+			</Object></Objects>"#[..],
+		)
+		.unwrap();
+		let config: Config = toml::from_str(include_str!("../../../../rotmguard.toml")).unwrap();
+
+		// the rest of the object is still there
+		let mut objects = HashMap::new();
+		parse(&config, &mut objects, &mut xml).unwrap();
+		let projectile = &objects[&0x1234].projectiles[&0];
+		assert_eq!(projectile.damage, Either::Left(50));
+		assert_eq!(projectile.size, 100);
+		assert_eq!(projectile.movement.speed, 0.0);
+	}
+}
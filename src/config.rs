@@ -1,8 +1,26 @@
+use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
-use std::{net::IpAddr, path::PathBuf, sync::Mutex};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt, fs,
+	io::Write,
+	net::IpAddr,
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::Mutex,
+};
+use tempfile::NamedTempFile;
+use toml_edit::{DocumentMut, Item, Table, Value};
 
 pub const CONFIG_PATH: &str = "rotmguard.toml";
 
+/// Settings that are only used on startup, changing them requires a restart
+const RESTART_REQUIRED: &[&str] = &[
+	"assets_res",
+	"settings.edit_assets",
+	"settings.damage_monitor",
+];
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
 	/// A path to the game's resources.assets.
@@ -14,19 +32,317 @@ pub struct Config {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Settings {
-	/// HP at which to autonexus. Recommended value 20
-	pub autonexus_hp: Mutex<i32>,
+	/// HP at which to autonexus. Recommended value 20.
+	/// Can also be a percentage of max HP, like "30%"
+	pub autonexus_hp: Mutex<Threshold>,
+	/// If not 0, also autonexus when taking the damage of the last this many seconds again,
+	/// plus the AoEs that are about to land, would bring HP below the threshold
+	#[serde(default)]
+	pub autonexus_predict: Mutex<f32>,
+	/// If true, the last 30 seconds of HP changes are written to a file when autonexus fires or
+	/// the player dies. They can also be seen through the damage monitor http server
+	#[serde(default)]
+	pub autonexus_log: Mutex<bool>,
+	/// Server (short name, like in /con) to reconnect to if escaping keeps failing
+	#[serde(default)]
+	pub autonexus_fallback: Mutex<Option<String>>,
+	/// If true, autonexus only tells when it would have nexused
+	#[serde(default)]
+	pub autonexus_dry_run: Mutex<bool>,
 	/// Reduces lag by blocking certain packets
 	pub antilag: Mutex<bool>,
 	/// If true, will activate developer mode.
 	pub dev_mode: Mutex<bool>,
+	/// Whether anti push is enabled when connecting
+	#[serde(default)]
+	pub antipush: Mutex<bool>,
+	/// Whether fake slow is enabled when connecting
+	#[serde(default)]
+	pub fakeslow: Mutex<bool>,
+	/// If true, settings changed with commands are saved to the config file right away
+	#[serde(default)]
+	pub autosave: Mutex<bool>,
 	/// Which client-side debuffs to disable
-	pub debuffs: Debuffs,
+	pub debuffs: Mutex<Debuffs>,
 	pub edit_assets: EditAssets,
 	pub damage_monitor: DamageMonitorConfig,
+	/// Game build versions to accept in addition to the ones rotmguard was made for.
+	/// On any other version all packets are passed through untouched.
+	#[serde(default)]
+	pub extra_build_versions: Mutex<Vec<String>>,
+	/// Overrides for specific classes or characters
+	#[serde(default)]
+	pub profiles: Mutex<Vec<Profile>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+/// Settings for a class and/or a character. Only what's set here overrides the normal settings.
+/// If several profiles match, one with a character name wins.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Profile {
+	/// Class name like "Wizard", or its object type
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub class: Option<String>,
+	/// Character name
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub autonexus_hp: Option<Threshold>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub antilag: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub fakeslow: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub debuffs: Option<Debuffs>,
+}
+
+/// An amount of HP, or a percentage of max HP when written like "30%"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+	Hp(i32),
+	Percent(f32),
+}
+
+impl Threshold {
+	/// The threshold in HP for the given max HP
+	pub fn hp(self, max_hp: i64) -> f32 {
+		match self {
+			Threshold::Hp(hp) => hp as f32,
+			Threshold::Percent(percent) => max_hp as f32 * percent / 100.0,
+		}
+	}
+}
+
+impl fmt::Display for Threshold {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Threshold::Hp(hp) => write!(f, "{hp} HP"),
+			Threshold::Percent(percent) => write!(f, "{percent}%"),
+		}
+	}
+}
+
+impl FromStr for Threshold {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim().strip_suffix('%') {
+			Some(percent) => match percent.trim().parse::<f32>() {
+				Ok(p) if (0.0..=100.0).contains(&p) => Ok(Threshold::Percent(p)),
+				_ => Err(format!("{s} is not a percentage between 0 and 100")),
+			},
+			None => match s.trim().parse::<i32>() {
+				Ok(hp) => Ok(Threshold::Hp(hp)),
+				Err(_) => Err(format!(
+					"{s} is not a whole number or a percentage like 30%"
+				)),
+			},
+		}
+	}
+}
+
+// an integer for HP, or a string for percentages
+impl Serialize for Threshold {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Threshold::Hp(hp) => serializer.serialize_i32(*hp),
+			Threshold::Percent(percent) => serializer.serialize_str(&format!("{percent}%")),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Threshold {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Raw {
+			Hp(i32),
+			Text(String),
+		}
+
+		match Raw::deserialize(deserializer)? {
+			Raw::Hp(hp) => Ok(Threshold::Hp(hp)),
+			Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+		}
+	}
+}
+
+impl Config {
+	/// Writes the config back to the given file, keeping the comments and formatting of
+	/// everything that didn't change. The file is replaced atomically.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+		let path = path.as_ref();
+
+		let mut document = match fs::read_to_string(path) {
+			Ok(raw) => raw.parse::<DocumentMut>().context("parsing config file")?,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
+			Err(e) => return Err(e).context("reading config file"),
+		};
+
+		let current = toml::to_string(self)
+			.context("serializing config")?
+			.parse::<DocumentMut>()
+			.context("parsing serialized config")?;
+
+		merge(document.as_table_mut(), current.as_table());
+
+		let dir = match path.parent() {
+			Some(dir) if !dir.as_os_str().is_empty() => dir,
+			_ => Path::new("."),
+		};
+		let mut file = NamedTempFile::new_in(dir).context("creating temporary file")?;
+		file.write_all(document.to_string().as_bytes())
+			.context("writing temporary file")?;
+		file.persist(path).context("replacing config file")?;
+
+		Ok(())
+	}
+	/// Reads the config file again and applies the new settings.
+	/// Returns the names of the changed settings. If anything that requires a restart
+	/// was changed, an error is returned and nothing is applied.
+	pub fn reload(&self, path: impl AsRef<Path>) -> Result<Vec<String>> {
+		let raw = fs::read_to_string(path).context("reading config file")?;
+		let new: Config = toml::from_str(&raw).context("parsing config file")?;
+
+		let old_table = toml::Table::try_from(self).context("serializing current config")?;
+		let new_table = toml::Table::try_from(&new).context("serializing new config")?;
+		let mut changed = Vec::new();
+		diff(&old_table, &new_table, "", &mut changed);
+
+		let restart: Vec<&str> = changed
+			.iter()
+			.filter(|name| {
+				RESTART_REQUIRED
+					.iter()
+					.any(|r| name == r || name.starts_with(&format!("{r}.")))
+			})
+			.map(|name| name.as_str())
+			.collect();
+		if !restart.is_empty() {
+			bail!("changing {} requires a restart", restart.join(", "));
+		}
+
+		self.settings.apply(new.settings);
+
+		Ok(changed)
+	}
+}
+
+impl Settings {
+	/// Checks the settings that name a server against the server list (short name -> address).
+	/// To be called whenever the settings are loaded
+	pub fn check_servers(&self, servers: &HashMap<String, String>) -> Result<()> {
+		if let Some(server) = &*self.autonexus_fallback.lock().unwrap()
+			&& !servers.contains_key(&server.to_ascii_lowercase())
+		{
+			bail!("autonexus_fallback {server:?} is not a known server and won't be used");
+		}
+
+		Ok(())
+	}
+	fn apply(&self, new: Settings) {
+		fn set<T>(current: &Mutex<T>, new: Mutex<T>) {
+			*current.lock().unwrap() = new.into_inner().unwrap();
+		}
+
+		// destructured so that new settings can't be forgotten here
+		let Settings {
+			autonexus_hp,
+			autonexus_predict,
+			autonexus_log,
+			autonexus_fallback,
+			autonexus_dry_run,
+			antilag,
+			dev_mode,
+			antipush,
+			fakeslow,
+			autosave,
+			debuffs,
+			edit_assets: _,
+			damage_monitor: _,
+			extra_build_versions,
+			profiles,
+		} = new;
+
+		set(&self.autonexus_hp, autonexus_hp);
+		set(&self.autonexus_predict, autonexus_predict);
+		set(&self.autonexus_log, autonexus_log);
+		set(&self.autonexus_fallback, autonexus_fallback);
+		set(&self.autonexus_dry_run, autonexus_dry_run);
+		set(&self.antilag, antilag);
+		set(&self.dev_mode, dev_mode);
+		set(&self.antipush, antipush);
+		set(&self.fakeslow, fakeslow);
+		set(&self.autosave, autosave);
+		set(&self.debuffs, debuffs);
+		set(&self.extra_build_versions, extra_build_versions);
+		set(&self.profiles, profiles);
+	}
+}
+
+// collects the dotted names of all values that differ
+fn diff(old: &toml::Table, new: &toml::Table, prefix: &str, changed: &mut Vec<String>) {
+	let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+
+	for key in keys {
+		let name = format!("{prefix}{key}");
+		match (old.get(key), new.get(key)) {
+			(Some(toml::Value::Table(old)), Some(toml::Value::Table(new))) => {
+				diff(old, new, &format!("{name}."), changed)
+			}
+			(old, new) if old != new => changed.push(name),
+			_ => {}
+		}
+	}
+}
+
+// updates the values in `document` that differ in `new`, keeping their comments
+fn merge(document: &mut Table, new: &Table) {
+	for (key, new_item) in new.iter() {
+		match (document.get_mut(key), new_item) {
+			(Some(Item::Table(old)), Item::Table(new)) => merge(old, new),
+			(Some(Item::ArrayOfTables(old)), Item::ArrayOfTables(new))
+				if old.len() == new.len() =>
+			{
+				for (old, new) in old.iter_mut().zip(new.iter()) {
+					merge(old, new);
+				}
+			}
+			(Some(Item::Value(old)), Item::Value(new)) => {
+				if !same_value(old, new) {
+					let decor = old.decor().clone();
+					*old = new.clone();
+					*old.decor_mut() = decor;
+				}
+			}
+			(Some(old), _) => *old = new_item.clone(),
+			(None, _) => {
+				document.insert(key, new_item.clone());
+			}
+		}
+	}
+}
+
+// ignoring formatting
+fn same_value(a: &Value, b: &Value) -> bool {
+	match (a, b) {
+		(Value::String(a), Value::String(b)) => a.value() == b.value(),
+		(Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+		(Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+		(Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+		(Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+		(Value::Array(a), Value::Array(b)) => {
+			a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b))
+		}
+		(Value::InlineTable(a), Value::InlineTable(b)) => {
+			a.len() == b.len()
+				&& a.iter()
+					.all(|(key, a)| b.get(key).is_some_and(|b| same_value(a, b)))
+		}
+		_ => false,
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct Debuffs {
 	/// If true will be disabled
 	#[serde(default)]
@@ -58,7 +374,7 @@ pub struct EditAssets {
 	pub force_debuffs: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DamageMonitorConfig {
 	/// Enables damage monitoring, see stats with /dmg command
 	pub enabled: bool,
@@ -70,4 +386,158 @@ pub struct DamageMonitorConfig {
 	pub bind_address: IpAddr,
 	/// What port to bind to, 0 for any
 	pub bind_port: u16,
+	/// Directory to save finished reports to, to see them after restarting. Not saved if not set
+	#[serde(default)]
+	pub archive_dir: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const RAW: &str = r#"# the game assets
+assets_res = "/some/path" # inline comment
+
+[settings]
+# HP below which to autonexus
+autonexus_hp = 20
+antilag = false # keep
+dev_mode = false
+extra_build_versions = [
+	"1.2.3",
+]
+
+[settings.debuffs]
+blind = true
+
+[settings.edit_assets]
+enabled = false
+force_debuffs = false
+
+# damage monitor
+[settings.damage_monitor]
+enabled = false
+keep_memory = 1
+open_browser = false
+bind_address = "127.0.0.1"
+bind_port = 0
+"#;
+
+	#[test]
+	fn save_keeps_comments() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("rotmguard.toml");
+		fs::write(&path, RAW).unwrap();
+
+		let config: Config = toml::from_str(RAW).unwrap();
+		*config.settings.autonexus_hp.lock().unwrap() = Threshold::Hp(50);
+		*config.settings.antilag.lock().unwrap() = true;
+		*config.settings.fakeslow.lock().unwrap() = true;
+		config.save(&path).unwrap();
+
+		let saved = fs::read_to_string(&path).unwrap();
+		assert!(
+			saved.starts_with("# the game assets\nassets_res = \"/some/path\" # inline comment\n")
+		);
+		assert!(saved.contains("# HP below which to autonexus\nautonexus_hp = 50\n"));
+		assert!(saved.contains("antilag = true # keep\n"));
+		// untouched values keep their formatting
+		assert!(saved.contains("extra_build_versions = [\n\t\"1.2.3\",\n]"));
+		assert!(saved.contains("# damage monitor\n[settings.damage_monitor]"));
+
+		let reloaded: Config = toml::from_str(&saved).unwrap();
+		assert_eq!(
+			*reloaded.settings.autonexus_hp.lock().unwrap(),
+			Threshold::Hp(50)
+		);
+		assert!(*reloaded.settings.fakeslow.lock().unwrap());
+		assert!(!*reloaded.settings.antipush.lock().unwrap());
+		assert!(reloaded.settings.debuffs.lock().unwrap().blind);
+
+		// nothing left behind
+		assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+	}
+
+	#[test]
+	fn reload() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("rotmguard.toml");
+		let config: Config = toml::from_str(RAW).unwrap();
+
+		fs::write(&path, RAW).unwrap();
+		assert!(config.reload(&path).unwrap().is_empty());
+
+		let changed = RAW
+			.replace("autonexus_hp = 20", "autonexus_hp = 30")
+			.replace("blind = true", "blind = false\nhexed = true");
+		fs::write(&path, changed).unwrap();
+		assert_eq!(
+			config.reload(&path).unwrap(),
+			[
+				"settings.autonexus_hp",
+				"settings.debuffs.blind",
+				"settings.debuffs.hexed"
+			]
+		);
+		assert_eq!(
+			*config.settings.autonexus_hp.lock().unwrap(),
+			Threshold::Hp(30)
+		);
+		assert!(config.settings.debuffs.lock().unwrap().hexed);
+
+		// nothing is applied if anything needs a restart
+		let restart = RAW
+			.replace("autonexus_hp = 20", "autonexus_hp = 40")
+			.replace("bind_port = 0", "bind_port = 8080");
+		fs::write(&path, restart).unwrap();
+		let error = config.reload(&path).unwrap_err().to_string();
+		assert_eq!(
+			error,
+			"changing settings.damage_monitor.bind_port requires a restart"
+		);
+		assert_eq!(
+			*config.settings.autonexus_hp.lock().unwrap(),
+			Threshold::Hp(30)
+		);
+
+		// or if it's invalid
+		fs::write(&path, "autonexus_hp = ").unwrap();
+		assert!(config.reload(&path).is_err());
+	}
+
+	#[test]
+	fn servers() {
+		let config: Config = toml::from_str(RAW).unwrap();
+		let servers = HashMap::from([("usw".to_owned(), "127.0.0.2".to_owned())]);
+		assert!(config.settings.check_servers(&servers).is_ok());
+
+		*config.settings.autonexus_fallback.lock().unwrap() = Some("USW".to_owned());
+		assert!(config.settings.check_servers(&servers).is_ok());
+
+		*config.settings.autonexus_fallback.lock().unwrap() = Some("uswest".to_owned());
+		assert!(config.settings.check_servers(&servers).is_err());
+	}
+
+	#[test]
+	fn thresholds() {
+		assert_eq!("250".parse(), Ok(Threshold::Hp(250)));
+		assert_eq!("30%".parse(), Ok(Threshold::Percent(30.0)));
+		assert!("150%".parse::<Threshold>().is_err());
+		assert!("abc".parse::<Threshold>().is_err());
+
+		assert_eq!(Threshold::Percent(25.0).hp(1800), 450.0);
+		assert_eq!(Threshold::Hp(100).hp(1800), 100.0);
+
+		let config: Config =
+			toml::from_str(&RAW.replace("autonexus_hp = 20", "autonexus_hp = \"12.5%\"")).unwrap();
+		assert_eq!(
+			*config.settings.autonexus_hp.lock().unwrap(),
+			Threshold::Percent(12.5)
+		);
+		assert!(
+			toml::to_string(&config)
+				.unwrap()
+				.contains("autonexus_hp = \"12.5%\"")
+		);
+	}
 }
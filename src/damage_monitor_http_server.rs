@@ -1,18 +1,31 @@
 use crate::config::Config;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use archive::{Archive, Unsaved};
 use askama::Template;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, combinators::BoxBody};
 use hyper::{Response, server::conn::http1::Builder, service::service_fn};
 use hyper_util::rt::TokioIo;
 use lru::LruCache;
+use routes::Route;
+use serde::{Deserialize, Serialize};
 use std::{
 	collections::BTreeMap,
+	convert::Infallible,
 	num::NonZeroUsize,
 	sync::{Arc, Mutex},
 };
-use tokio::net::TcpListener;
+use tokio::{net::TcpListener, sync::watch};
 use tracing::{error, info};
 
+mod archive;
+mod live;
 mod report_util;
+mod routes;
+
+pub use live::LiveUpdate;
+
+type Body = BoxBody<Bytes, Infallible>;
 
 pub enum DamageMonitorHttp {
 	Disabled,
@@ -26,9 +39,17 @@ pub struct Inner {
 	// id -> (lowercase map name, report)
 	live_pages: LruCache<usize, (String, Report)>,
 	memory: LruCache<usize, (String, Report)>,
+	timeline_counter: usize,
+	timelines: LruCache<usize, Timeline>,
+	death_counter: usize,
+	deaths: LruCache<usize, DeathRecap>,
+	// if enabled
+	archive: Option<Archive>,
+	// json of the latest LiveUpdate, empty until the first one
+	live: watch::Sender<String>,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize, Deserialize)]
 #[template(path = "damage_report.html")]
 pub struct Report {
 	pub map_name: String,
@@ -38,13 +59,26 @@ pub struct Report {
 	pub all_enemies: BTreeMap<u32, Option<String>>, // enemy object id -> item sprite base64
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct EnemyTab {
 	pub name: String,
 	pub object_id: u32,
 	pub total_damage: i64,
 	pub players: Vec<PlayerRow>,
+	/// Milliseconds from the first damage to the last, 0 in reports made before this was saved.
+	/// Not a kill time, the enemy might have gotten away or the player might have left.
+	#[serde(default, alias = "time_to_kill")]
+	pub fight_duration: u32,
+	/// The same for each phase. Bosses become invulnerable between phases, so that's where one ends.
+	/// Empty in reports made before this was saved
+	#[serde(default)]
+	pub phases: Vec<u32>,
 }
 
+/// How many seconds the burst DPS is averaged over
+pub const BURST_SECONDS: usize = 5;
+
+#[derive(Serialize, Deserialize)]
 pub struct PlayerRow {
 	pub name: String,
 	pub is_self: bool,
@@ -52,14 +86,159 @@ pub struct PlayerRow {
 	pub damage: i64,
 	pub damage_percent: String,
 	pub items: [Option<PlayerItem>; 4],
+	/// Damage done in each second since the enemy was first damaged
+	#[serde(default)]
+	pub dps: Vec<i64>,
+	/// The highest average DPS over a few seconds
+	#[serde(default)]
+	pub burst_dps: i64,
+	/// The second the burst window starts at
+	#[serde(default)]
+	pub burst_start: usize,
+	/// What the damage was done with, sorted by damage. Only known for our own damage
+	#[serde(default)]
+	pub sources: Vec<DamageSource>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DamageSource {
+	/// "Weapon", "Ability" or "Summon"
+	pub kind: String,
+	/// The item, or the summon
+	pub object_type: u32,
+	pub name: String,
+	pub damage: i64,
+	/// Of the damage of the player
+	pub damage_percent: String,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct PlayerItem {
 	pub id: u32,
 	pub name: String,
 	pub enchantments: Vec<String>,
 }
 
+/// What happened to the HP in the last seconds before autonexus fired or the player died
+#[derive(Template)]
+#[template(path = "autonexus_timeline.html")]
+pub struct Timeline {
+	pub reason: String,
+	pub time: String,
+	pub rows: Vec<TimelineRow>,
+}
+
+pub struct TimelineRow {
+	/// Before the end of the timeline, like "-1.25s"
+	pub time: String,
+	pub description: String,
+	/// Like "-120" or "+5.2", empty if the event didn't change HP
+	pub change: String,
+	/// Simulated HP after the event
+	pub hp: i64,
+	pub server_hp: i64,
+}
+
+/// Where the damage came from in the last seconds before the player died
+#[derive(Template)]
+#[template(path = "death_recap.html")]
+pub struct DeathRecap {
+	pub time: String,
+	pub max_hp: i64,
+	/// Sorted by damage, descending
+	pub sources: Vec<RecapSource>,
+	pub hits: Vec<RecapHit>,
+	pub all_enemies: BTreeMap<u32, Option<String>>, // enemy object type -> sprite base64
+}
+
+pub struct RecapSource {
+	pub name: String,
+	/// None for ground damage
+	pub object_type: Option<u32>,
+	pub hits: usize,
+	pub damage: i64,
+}
+
+pub struct RecapHit {
+	/// Before the death, like "-1.25s"
+	pub time: String,
+	pub source: String,
+	pub object_type: Option<u32>,
+	pub damage: i64,
+	/// Names of the conditions the player had
+	pub conditions: String,
+	/// Simulated HP after the hit
+	pub hp: i64,
+	pub server_hp: i64,
+}
+
+/// Updates itself with the damage of the current dungeon, see `live`
+#[derive(Template)]
+#[template(path = "live_report.html")]
+pub struct LivePage;
+
+/// All reports the server has, newest first in each list
+#[derive(Template, Serialize)]
+#[template(path = "report_index.html")]
+pub struct ReportIndex {
+	pub live: Vec<ReportSummary>,
+	pub memory: Vec<ReportSummary>,
+	pub archive: Vec<ReportSummary>,
+}
+
+#[derive(Serialize)]
+pub struct ReportSummary {
+	/// "live", "memory" or "archive"
+	pub kind: &'static str,
+	pub id: usize,
+	pub map_name: String,
+	pub time: String,
+	pub top_player: Option<String>,
+	pub top_damage: Option<i64>,
+}
+
+impl Report {
+	/// (name, damage) of the player with the most damage to all enemies together
+	pub fn top_player(&self) -> Option<(String, i64)> {
+		top_player(
+			self.enemy_tabs
+				.iter()
+				.flat_map(|tab| &tab.players)
+				.map(|p| (p.name.as_str(), p.damage)),
+		)
+	}
+}
+
+// sums up the damage of each player
+fn top_player<'a>(damage: impl Iterator<Item = (&'a str, i64)>) -> Option<(String, i64)> {
+	let mut total: BTreeMap<&str, i64> = BTreeMap::new();
+	for (name, damage) in damage {
+		*total.entry(name).or_default() += damage;
+	}
+
+	total
+		.into_iter()
+		.max_by_key(|(_, damage)| *damage)
+		.map(|(name, damage)| (name.to_owned(), damage))
+}
+
+impl Inner {
+	fn new(keep_memory: u32, archive: Option<Archive>) -> Self {
+		Self {
+			live_counter: 0,
+			live_pages: LruCache::new(NonZeroUsize::new(15).unwrap()),
+			memory_counter: 0,
+			memory: LruCache::new(NonZeroUsize::new(keep_memory as usize).unwrap()),
+			timeline_counter: 0,
+			timelines: LruCache::new(NonZeroUsize::new(15).unwrap()),
+			death_counter: 0,
+			deaths: LruCache::new(NonZeroUsize::new(15).unwrap()),
+			archive,
+			live: watch::Sender::new(String::new()),
+		}
+	}
+}
+
 impl DamageMonitorHttp {
 	pub async fn new(config: &Config) -> Result<Self> {
 		if !config.settings.damage_monitor.enabled {
@@ -75,14 +254,19 @@ impl DamageMonitorHttp {
 
 		info!("Damage monitor http server bound on {addr}");
 
-		let inner = Arc::new(Mutex::new(Inner {
-			live_counter: 0,
-			live_pages: LruCache::new(NonZeroUsize::new(15).unwrap()),
-			memory_counter: 0,
-			memory: LruCache::new(
-				NonZeroUsize::new(config.settings.damage_monitor.keep_memory as usize).unwrap(),
-			),
-		}));
+		let archive = match &config.settings.damage_monitor.archive_dir {
+			Some(dir) => {
+				let archive = Archive::open(dir).context("opening the report archive")?;
+				info!("{} archived reports", archive.reports.len());
+				Some(archive)
+			}
+			None => None,
+		};
+
+		let inner = Arc::new(Mutex::new(Inner::new(
+			config.settings.damage_monitor.keep_memory,
+			archive,
+		)));
 
 		let inner_clone = Arc::clone(&inner);
 		tokio::spawn(async move {
@@ -109,6 +293,34 @@ impl DamageMonitorHttp {
 		}
 	}
 	pub fn add_final_report(&self, page: Report) {
+		// reports are finalised whenever a connection ends, even if nothing is going to serve them
+		let DamageMonitorHttp::Enabled { port: _, inner } = self else {
+			return;
+		};
+		// the nexus, vault and such
+		if page.enemy_tabs.is_empty() {
+			return;
+		}
+
+		let archive_dir = self.inner().archive.as_ref().map(|a| a.dir().to_owned());
+		if let Some(dir) = archive_dir {
+			match Unsaved::new(&page) {
+				Ok(unsaved) => {
+					let inner = Arc::clone(inner);
+					// not blocking the connection (or the server) on the disk
+					tokio::task::spawn_blocking(move || match unsaved.write(&dir) {
+						Ok(archived) => {
+							if let Some(archive) = &mut inner.lock().unwrap().archive {
+								archive.add(archived);
+							}
+						}
+						Err(e) => error!("Error archiving report: {e:?}"),
+					});
+				}
+				Err(e) => error!("Error archiving report: {e:?}"),
+			}
+		}
+
 		let mut inner = self.inner();
 		let id = inner.memory_counter;
 		inner.memory_counter += 1;
@@ -126,6 +338,53 @@ impl DamageMonitorHttp {
 
 		id
 	}
+	/// None if the server is disabled
+	pub fn add_timeline(&self, page: Timeline) -> Option<usize> {
+		if let DamageMonitorHttp::Disabled = self {
+			return None;
+		}
+
+		let mut inner = self.inner();
+		let id = inner.timeline_counter;
+		inner.timeline_counter += 1;
+
+		inner.timelines.put(id, page);
+
+		Some(id)
+	}
+	/// None if the server is disabled
+	pub fn add_death_recap(&self, page: DeathRecap) -> Option<usize> {
+		if let DamageMonitorHttp::Disabled = self {
+			return None;
+		}
+
+		let mut inner = self.inner();
+		let id = inner.death_counter;
+		inner.death_counter += 1;
+
+		inner.deaths.put(id, page);
+
+		Some(id)
+	}
+	/// Whether anyone has the live page open, so the update isn't made for nothing
+	pub fn live_subscribed(&self) -> bool {
+		match self {
+			DamageMonitorHttp::Disabled => false,
+			DamageMonitorHttp::Enabled { port: _, inner } => {
+				inner.lock().unwrap().live.receiver_count() > 0
+			}
+		}
+	}
+	pub fn publish_live(&self, update: &LiveUpdate) {
+		let json = serde_json::to_string(update).unwrap();
+		self.inner().live.send_if_modified(|old| {
+			if *old == json {
+				return false;
+			}
+			*old = json;
+			true
+		});
+	}
 	pub fn find_memory_by_name(&self, substr: &str) -> Option<usize> {
 		let lowercase_pat = substr.to_lowercase();
 		// lru iterates in most recently used order
@@ -137,6 +396,17 @@ impl DamageMonitorHttp {
 
 		None
 	}
+	/// The most recent archived report with the map name containing the substring
+	pub fn find_archived_by_name(&self, substr: &str) -> Option<usize> {
+		let lowercase_pat = substr.to_lowercase();
+		let inner = self.inner();
+		let archive = inner.archive.as_ref()?;
+
+		archive
+			.reports
+			.iter()
+			.rposition(|report| report.map_name.to_lowercase().contains(&lowercase_pat))
+	}
 	pub fn find_memory_by_offset(&self, offset: usize) -> Option<usize> {
 		let inner = self.inner();
 		let index = inner.memory_counter.checked_sub(offset)?;
@@ -150,63 +420,48 @@ impl DamageMonitorHttp {
 }
 
 async fn server(listener: TcpListener, inner: Arc<Mutex<Inner>>) -> Result<()> {
-	let service = service_fn(async |request| {
-		let path = request.uri().path().strip_prefix('/').unwrap();
-
-		let (page_type, id) = match path.split_once('/') {
-			Some(x) => x,
-			None => {
-				return Ok(Response::builder()
-					.status(400)
-					.body("Invalid path".to_owned())
-					.unwrap());
-			}
-		};
+	loop {
+		let (stream, _) = listener.accept().await?;
+		let inner = Arc::clone(&inner);
 
-		let mut inner = inner.lock().unwrap();
-		let pages = match page_type {
-			"live" => &mut inner.live_pages,
-			"memory" => &mut inner.memory,
-			other => {
-				return Ok(Response::builder()
-					.status(400)
-					.body(format!("Invalid page type {other:?}"))
-					.unwrap());
-			}
-		};
+		// the live event stream stays open, so connections can't be served one by one
+		tokio::spawn(async move {
+			let service = service_fn(|request| {
+				let path = request.uri().path().strip_prefix('/').unwrap().to_owned();
+				let inner = Arc::clone(&inner);
 
-		let id = match id.parse::<usize>() {
-			Ok(x) => x,
-			Err(e) => {
-				return Ok(Response::builder()
-					.status(400)
-					.body(format!("Invalid id {id:?}: {e}"))
-					.unwrap());
-			}
-		};
+				async move { Ok::<_, Infallible>(handle(&inner, &path).await) }
+			});
 
-		let response = match pages.get(&id) {
-			Some((_name, page)) => {
-				let page = page.render().unwrap();
-				Response::builder().status(200).body(page).unwrap()
+			if let Err(e) = Builder::new()
+				.serve_connection(TokioIo::new(stream), service)
+				.await
+			{
+				error!("Damage monitor http connection: {e}");
 			}
-			None => Response::builder()
-				.status(400)
-				.body(format!(
-					"Report {id} not found. Available reports: {:?}",
-					pages.iter().map(|(k, _)| k).collect::<Vec<_>>()
-				))
-				.unwrap(),
-		};
+		});
+	}
+}
 
-		Ok::<_, &'static str>(response)
-	});
+async fn handle(inner: &Mutex<Inner>, path: &str) -> Response<Body> {
+	if path == "live/events" {
+		let receiver = inner.lock().unwrap().live.subscribe();
+		return live::events(receiver);
+	}
 
-	loop {
-		let (stream, _) = listener.accept().await?;
+	// the lock is only held while routing, game connections need it too
+	let route = routes::route(&mut inner.lock().unwrap(), path);
+	let response = match route {
+		Route::Ready(response) => response,
+		Route::Archived(archived) => match tokio::task::spawn_blocking(|| archived.respond()).await
+		{
+			Ok(response) => response,
+			Err(e) => {
+				error!("Loading archived report: {e}");
+				Response::builder().status(500).body(e.to_string()).unwrap()
+			}
+		},
+	};
 
-		Builder::new()
-			.serve_connection(TokioIo::new(stream), service.clone())
-			.await?;
-	}
+	response.map(|body| Full::new(Bytes::from(body)).boxed())
 }
@@ -0,0 +1,292 @@
+//! Finalised reports saved to disk as JSON, so they're still there after restarting.
+//!
+//! Only the map name and time of each report are kept in memory, the report itself is read
+//! when it's requested.
+
+use super::{Report, top_player};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{
+	fs::{self, OpenOptions},
+	io::{ErrorKind, Write},
+	path::{Path, PathBuf},
+};
+use tracing::warn;
+
+pub struct Archive {
+	dir: PathBuf,
+	/// Oldest first, the index is the id
+	pub reports: Vec<ArchivedReport>,
+}
+
+pub struct ArchivedReport {
+	pub path: PathBuf,
+	pub map_name: String,
+	pub time: String,
+	/// (name, damage)
+	pub top_player: Option<(String, i64)>,
+}
+
+/// A report serialized and ready to be written, so the writing can happen on another thread
+pub struct Unsaved {
+	json: Vec<u8>,
+	map_name: String,
+	time: String,
+	top_player: Option<(String, i64)>,
+}
+
+// only what's needed for the index, without deserializing everything else
+#[derive(Deserialize)]
+struct Header {
+	map_name: String,
+	time: String,
+	enemy_tabs: Vec<HeaderTab>,
+}
+
+#[derive(Deserialize)]
+struct HeaderTab {
+	players: Vec<HeaderPlayer>,
+}
+
+#[derive(Deserialize)]
+struct HeaderPlayer {
+	name: String,
+	damage: i64,
+}
+
+impl Archive {
+	/// Reads the index of all reports in the directory, creating it if it doesn't exist
+	pub fn open(dir: &Path) -> Result<Self> {
+		fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+
+		let mut reports = Vec::new();
+		for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+			let path = entry?.path();
+			if path.extension().is_none_or(|ext| ext != "json") {
+				continue;
+			}
+
+			let header = fs::read(&path)
+				.context("reading file")
+				.and_then(|raw| serde_json::from_slice::<Header>(&raw).context("parsing report"));
+			match header {
+				Ok(header) => reports.push(ArchivedReport {
+					path,
+					top_player: top_player(
+						header
+							.enemy_tabs
+							.iter()
+							.flat_map(|tab| &tab.players)
+							.map(|p| (p.name.as_str(), p.damage)),
+					),
+					map_name: header.map_name,
+					time: header.time,
+				}),
+				Err(e) => warn!("Skipping archived report {}: {e:?}", path.display()),
+			}
+		}
+
+		// the time format sorts the same way as the times
+		reports.sort_by(|a, b| a.time.cmp(&b.time));
+
+		Ok(Self {
+			dir: dir.to_owned(),
+			reports,
+		})
+	}
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+	/// Adds a written report to the index
+	pub fn add(&mut self, report: ArchivedReport) {
+		self.reports.push(report);
+	}
+}
+
+/// Reads an archived report, which can be a few MB
+pub fn load(path: &Path) -> Result<Report> {
+	let raw = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+
+	serde_json::from_slice(&raw).context("parsing report")
+}
+
+impl Unsaved {
+	pub fn new(report: &Report) -> Result<Self> {
+		Ok(Self {
+			json: serde_json::to_vec(report).context("serializing report")?,
+			map_name: report.map_name.clone(),
+			time: report.time.clone(),
+			top_player: report.top_player(),
+		})
+	}
+	/// Writes the report to the directory, returning what to add to the index
+	pub fn write(self, dir: &Path) -> Result<ArchivedReport> {
+		let name: String = format!("{} {}", self.time, self.map_name)
+			.chars()
+			.map(|c| if c.is_alphanumeric() { c } else { '_' })
+			.collect();
+
+		// two dungeons ended in the same second, or the same map twice
+		let mut n = 1;
+		let (path, mut file) = loop {
+			let path = match n {
+				1 => dir.join(format!("{name}.json")),
+				n => dir.join(format!("{name}_{n}.json")),
+			};
+			match OpenOptions::new().write(true).create_new(true).open(&path) {
+				Ok(file) => break (path, file),
+				Err(e) if e.kind() == ErrorKind::AlreadyExists => n += 1,
+				Err(e) => return Err(e).with_context(|| format!("creating {}", path.display())),
+			}
+		};
+		file.write_all(&self.json)
+			.with_context(|| format!("writing {}", path.display()))?;
+
+		Ok(ArchivedReport {
+			path,
+			map_name: self.map_name,
+			time: self.time,
+			top_player: self.top_player,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::damage_monitor_http_server::{
+		DamageMonitorHttp, EnemyTab, Inner, PlayerRow, handle,
+	};
+	use http_body_util::BodyExt;
+	use std::{
+		sync::{Arc, Mutex},
+		time::Duration,
+	};
+
+	fn report(map_name: &str, time: &str) -> Report {
+		Report {
+			map_name: map_name.to_owned(),
+			time: time.to_owned(),
+			enemy_tabs: vec![EnemyTab {
+				name: "Boss".to_owned(),
+				object_id: 5,
+				total_damage: 1000,
+				players: vec![PlayerRow {
+					name: "Someone".to_owned(),
+					is_self: true,
+					status: ' ',
+					damage: 1000,
+					damage_percent: "100.00".to_owned(),
+					items: [None, None, None, None],
+					dps: vec![1000],
+					burst_dps: 1000,
+					burst_start: 0,
+					sources: Vec::new(),
+				}],
+				fight_duration: 500,
+				phases: Vec::new(),
+			}],
+			all_items: Default::default(),
+			all_enemies: [(5, Some("c3ByaXRl".to_owned()))].into(),
+		}
+	}
+
+	#[test]
+	fn round_trip() {
+		let dir = tempfile::tempdir().unwrap();
+
+		Archive::open(dir.path()).unwrap();
+		Unsaved::new(&report("Shatters", "2025-01-02 10:00:00"))
+			.unwrap()
+			.write(dir.path())
+			.unwrap();
+		Unsaved::new(&report("Shatters", "2025-01-01 10:00:00"))
+			.unwrap()
+			.write(dir.path())
+			.unwrap();
+		Unsaved::new(&report("Shatters", "2025-01-01 10:00:00"))
+			.unwrap()
+			.write(dir.path())
+			.unwrap();
+		fs::write(dir.path().join("broken.json"), "{").unwrap();
+		assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 4);
+
+		// reopened after a restart, oldest first
+		let archive = Archive::open(dir.path()).unwrap();
+		let times: Vec<_> = archive.reports.iter().map(|r| r.time.as_str()).collect();
+		assert_eq!(
+			times,
+			[
+				"2025-01-01 10:00:00",
+				"2025-01-01 10:00:00",
+				"2025-01-02 10:00:00"
+			]
+		);
+
+		let loaded = load(&archive.reports[2].path).unwrap();
+		assert_eq!(loaded.time, "2025-01-02 10:00:00");
+		assert_eq!(loaded.enemy_tabs[0].players[0].name, "Someone");
+		assert_eq!(loaded.all_enemies, report("", "").all_enemies);
+		assert_eq!(
+			archive.reports[2].top_player,
+			Some(("Someone".to_owned(), 1000))
+		);
+	}
+
+	#[tokio::test]
+	async fn final_reports() {
+		let dir = tempfile::tempdir().unwrap();
+		let inner = Arc::new(Mutex::new(Inner::new(
+			5,
+			Some(Archive::open(dir.path()).unwrap()),
+		)));
+		let http = DamageMonitorHttp::Enabled {
+			port: 0,
+			inner: Arc::clone(&inner),
+		};
+
+		// the nexus, nothing happened there
+		let mut empty = report("Nexus", "2025-01-01 10:00:00");
+		empty.enemy_tabs.clear();
+		http.add_final_report(empty);
+		http.add_final_report(report("Shatters", "2025-01-01 10:05:00"));
+
+		// written in the background
+		for _ in 0..100 {
+			if !inner
+				.lock()
+				.unwrap()
+				.archive
+				.as_ref()
+				.unwrap()
+				.reports
+				.is_empty()
+			{
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+
+		{
+			let inner = inner.lock().unwrap();
+			let archived: Vec<_> = inner
+				.archive
+				.as_ref()
+				.unwrap()
+				.reports
+				.iter()
+				.map(|r| r.map_name.as_str())
+				.collect();
+			assert_eq!(archived, ["Shatters"]);
+			assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+			assert_eq!(inner.memory.len(), 1);
+		}
+
+		// read back without holding the lock
+		let response = handle(&inner, "api/reports/archive/0").await;
+		assert_eq!(response.status(), 200);
+		let body = response.into_body().collect().await.unwrap().to_bytes();
+		let loaded: Report = serde_json::from_slice(&body).unwrap();
+		assert_eq!(loaded.map_name, "Shatters");
+	}
+}
@@ -0,0 +1,83 @@
+//! The damage of the current dungeon, pushed to the browser as it happens using Server-Sent
+//! Events. `/live/stream` is the page and `/live/events` is the event stream it subscribes to.
+
+use super::{Body, EnemyTab};
+use bytes::Bytes;
+use futures::stream;
+use http_body_util::{BodyExt, StreamBody};
+use hyper::{Response, body::Frame};
+use serde::Serialize;
+use std::convert::Infallible;
+use tokio::sync::watch;
+
+/// What the live page shows, without the sprites to keep it small
+#[derive(Serialize)]
+pub struct LiveUpdate {
+	pub map_name: String,
+	pub enemy_tabs: Vec<EnemyTab>,
+}
+
+/// Streams every update sent on the channel, starting with the latest one
+pub fn events(mut receiver: watch::Receiver<String>) -> Response<Body> {
+	receiver.mark_changed();
+
+	let events = stream::unfold(receiver, async |mut receiver| {
+		let data = loop {
+			// the sender is never dropped while the server is running
+			receiver.changed().await.ok()?;
+			let data = receiver.borrow_and_update();
+			// nothing was published yet
+			if !data.is_empty() {
+				break data.clone();
+			}
+		};
+		let event = format!("data: {data}\n\n");
+
+		Some((
+			Ok::<_, Infallible>(Frame::data(Bytes::from(event))),
+			receiver,
+		))
+	});
+
+	Response::builder()
+		.status(200)
+		.header("content-type", "text/event-stream")
+		.header("cache-control", "no-cache")
+		.body(StreamBody::new(events).boxed())
+		.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::damage_monitor_http_server::{DamageMonitorHttp, Inner, handle};
+	use std::sync::{Arc, Mutex};
+
+	#[tokio::test]
+	async fn stream() {
+		let inner = Arc::new(Mutex::new(Inner::new(5, None)));
+		let http = DamageMonitorHttp::Enabled {
+			port: 0,
+			inner: Arc::clone(&inner),
+		};
+		assert!(!http.live_subscribed());
+
+		let response = handle(&inner, "live/events").await;
+		assert_eq!(response.headers()["content-type"], "text/event-stream");
+		assert!(http.live_subscribed());
+
+		let mut body = response.into_body();
+		http.publish_live(&LiveUpdate {
+			map_name: "Nest".to_owned(),
+			enemy_tabs: Vec::new(),
+		});
+		let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+		assert_eq!(
+			frame,
+			"data: {\"map_name\":\"Nest\",\"enemy_tabs\":[]}\n\n".as_bytes()
+		);
+
+		drop(body);
+		assert!(!http.live_subscribed());
+	}
+}
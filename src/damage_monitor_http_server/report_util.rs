@@ -1,5 +1,29 @@
+use super::{BURST_SECONDS, EnemyTab};
 use base64::{Engine, prelude::BASE64_STANDARD};
-use std::sync::OnceLock;
+use std::{fmt::Write, sync::OnceLock};
+
+// the size of the DPS graph, it's stretched to the width of the page
+pub const GRAPH_WIDTH: f64 = 600.0;
+pub const GRAPH_HEIGHT: f64 = 120.0;
+// only the top players get a line, otherwise it's unreadable
+const LINE_COLORS: [&str; 8] = [
+	"#E6655C", "#5CB8E6", "#E6C35C", "#8C5CE6", "#5CE68A", "#E65CB8", "#E6955C", "#C6D4FF",
+];
+
+pub struct DpsGraph {
+	pub lines: Vec<DpsLine>,
+	pub peak_dps: i64,
+	/// (x, width) of the burst window of the player, if they're on the graph
+	pub burst: Option<(f64, f64)>,
+}
+
+pub struct DpsLine {
+	pub name: String,
+	pub color: &'static str,
+	pub is_self: bool,
+	/// For the svg polyline
+	pub points: String,
+}
 
 pub fn icon() -> &'static str {
 	static ICON: OnceLock<String> = OnceLock::new();
@@ -34,3 +58,124 @@ pub fn format_number(n: i64) -> String {
 		_ => format!("{:.0}G", n as f64 / 1_000_000_000.0),
 	}
 }
+
+pub fn format_seconds(ms: u32) -> String {
+	format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+/// The color of the player's line on the DPS graph, by their place in the tab
+pub fn line_color(index: usize) -> Option<&'static str> {
+	LINE_COLORS.get(index).copied()
+}
+
+pub fn dps_graph(tab: &EnemyTab) -> DpsGraph {
+	let players = &tab.players[..tab.players.len().min(LINE_COLORS.len())];
+	let seconds = players.iter().map(|p| p.dps.len()).max().unwrap_or(0);
+	let peak_dps = players
+		.iter()
+		.flat_map(|p| &p.dps)
+		.copied()
+		.max()
+		.unwrap_or(0)
+		.max(1);
+
+	// a single second is drawn as a flat line
+	let x = |second: usize| second as f64 * GRAPH_WIDTH / (seconds.max(2) - 1) as f64;
+	let y = |dps: i64| GRAPH_HEIGHT - dps as f64 * GRAPH_HEIGHT / peak_dps as f64;
+
+	let lines = players
+		.iter()
+		.zip(LINE_COLORS)
+		.map(|(player, color)| {
+			let mut points = String::new();
+			for (second, &dps) in player.dps.iter().enumerate() {
+				write!(points, "{:.1},{:.1} ", x(second), y(dps)).unwrap();
+			}
+			if player.dps.len() == 1 {
+				write!(points, "{:.1},{:.1}", GRAPH_WIDTH, y(player.dps[0])).unwrap();
+			}
+
+			DpsLine {
+				name: player.name.clone(),
+				color,
+				is_self: player.is_self,
+				points: points.trim_end().to_owned(),
+			}
+		})
+		.collect();
+
+	let burst = players.iter().find(|p| p.is_self).map(|player| {
+		let end = (player.burst_start + BURST_SECONDS).min(seconds.max(2) - 1);
+		(x(player.burst_start), x(end) - x(player.burst_start))
+	});
+
+	DpsGraph {
+		lines,
+		peak_dps,
+		burst,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::damage_monitor_http_server::{DamageSource, PlayerRow, Report};
+	use askama::Template;
+
+	fn row(name: &str, is_self: bool, dps: Vec<i64>) -> PlayerRow {
+		PlayerRow {
+			name: name.to_owned(),
+			is_self,
+			status: ' ',
+			damage: dps.iter().sum(),
+			damage_percent: String::new(),
+			items: [None, None, None, None],
+			dps,
+			burst_dps: 0,
+			burst_start: 1,
+			sources: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn graph() {
+		let mut tab = EnemyTab {
+			name: "Boss".to_owned(),
+			object_id: 1,
+			total_damage: 0,
+			players: vec![row("A", false, vec![0, 100, 50]), row("B", true, vec![20])],
+			fight_duration: 2500,
+			phases: vec![1000, 500],
+		};
+
+		tab.players[1].sources.push(DamageSource {
+			kind: "Ability".to_owned(),
+			object_type: 7,
+			name: "Some Spell".to_owned(),
+			damage: 20,
+			damage_percent: "100.00".to_owned(),
+		});
+
+		let graph = dps_graph(&tab);
+		assert_eq!(graph.peak_dps, 100);
+		assert_eq!(graph.lines[0].points, "0.0,120.0 300.0,0.0 600.0,60.0");
+		// shorter than the fight
+		assert_eq!(graph.lines[1].points, "0.0,96.0 600.0,96.0");
+		assert_eq!(graph.lines[1].color, LINE_COLORS[1]);
+		assert_eq!(graph.burst, Some((300.0, 300.0)));
+
+		let report = Report {
+			map_name: "Nest".to_owned(),
+			time: String::new(),
+			enemy_tabs: vec![tab],
+			all_items: Default::default(),
+			all_enemies: Default::default(),
+		};
+		let page = report.render().unwrap();
+		assert!(page.contains("Fight duration 2.5s"));
+		assert!(page.contains("Phase 1 1.0s, Phase 2 0.5s"));
+		assert!(page.contains(r#"<polyline points="0.0,120.0 300.0,0.0 600.0,60.0""#));
+		assert!(page.contains(r#"<div class="item-slot item-id-7"></div>"#));
+		assert!(page.contains(r#"<span class="source-name">Some Spell</span>"#));
+	}
+}
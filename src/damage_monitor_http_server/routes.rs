@@ -0,0 +1,309 @@
+//! Which page to serve for which path.
+//!
+//! `/` is the index of all reports, `/api/reports` is the same as JSON, and
+//! `/api/reports/<type>/<id>` is a single report as JSON. Live, memory and archived reports are
+//! numbered separately, so the type is needed to tell which one is meant.
+//!
+//! A report that doesn't exist is 404, a path that doesn't make sense is 400. `/live/events` is
+//! handled separately since it's not a single response.
+
+use super::{Inner, LivePage, Report, ReportIndex, ReportSummary, archive};
+use askama::Template;
+use hyper::Response;
+use lru::LruCache;
+use serde::Serialize;
+use std::path::PathBuf;
+
+pub enum Route {
+	Ready(Response<String>),
+	/// Has to be read from disk first, which shouldn't happen while [`Inner`] is locked
+	Archived(Archived),
+}
+
+pub struct Archived {
+	id: usize,
+	path: PathBuf,
+	json: bool,
+}
+
+impl From<Response<String>> for Route {
+	fn from(response: Response<String>) -> Self {
+		Route::Ready(response)
+	}
+}
+
+impl Archived {
+	/// Reads and parses the report, blocking
+	pub fn respond(self) -> Response<String> {
+		match archive::load(&self.path) {
+			Ok(report) if self.json => json(&report),
+			Ok(report) => html(&report),
+			Err(e) => error(
+				500,
+				format!("Error loading archived report {}: {e:?}", self.id),
+			),
+		}
+	}
+}
+
+pub fn route(inner: &mut Inner, path: &str) -> Route {
+	if path.is_empty() {
+		return html(&index(inner)).into();
+	}
+	if path == "api/reports" {
+		return json(&index(inner)).into();
+	}
+	if let Some(path) = path.strip_prefix("api/reports/") {
+		return api_report(inner, path);
+	}
+	if path == "live/stream" {
+		return html(&LivePage).into();
+	}
+
+	let (page_type, id) = match parse(path) {
+		Ok(x) => x,
+		Err(message) => return error(400, message).into(),
+	};
+
+	let pages = match page_type {
+		"live" => &mut inner.live_pages,
+		"memory" => &mut inner.memory,
+		"autonexus" => {
+			return match inner.timelines.get(&id) {
+				Some(page) => html(page),
+				None => error(404, format!("Autonexus timeline {id} not found")),
+			}
+			.into();
+		}
+		"archive" => return archived(inner, id, false),
+		"death" => {
+			return match inner.deaths.get(&id) {
+				Some(page) => html(page),
+				None => error(404, format!("Death recap {id} not found")),
+			}
+			.into();
+		}
+		other => return error(400, format!("Invalid page type {other:?}")).into(),
+	};
+
+	match pages.get(&id) {
+		Some((_name, page)) => html(page),
+		None => error(
+			404,
+			format!(
+				"Report {id} not found. Available reports: {:?}",
+				pages.iter().map(|(k, _)| k).collect::<Vec<_>>()
+			),
+		),
+	}
+	.into()
+}
+
+// `<type>/<id>`
+fn parse(path: &str) -> Result<(&str, usize), String> {
+	let Some((page_type, id)) = path.split_once('/') else {
+		return Err("Invalid path".to_owned());
+	};
+
+	match id.parse::<usize>() {
+		Ok(id) => Ok((page_type, id)),
+		Err(e) => Err(format!("Invalid id {id:?}: {e}")),
+	}
+}
+
+fn api_report(inner: &mut Inner, path: &str) -> Route {
+	let (page_type, id) = match parse(path) {
+		Ok(x) => x,
+		Err(message) => return error(400, message).into(),
+	};
+
+	let report = match page_type {
+		"live" => inner.live_pages.get(&id).map(|(_name, report)| report),
+		"memory" => inner.memory.get(&id).map(|(_name, report)| report),
+		"archive" => return archived(inner, id, true),
+		other => return error(400, format!("Invalid report type {other:?}")).into(),
+	};
+
+	match report {
+		Some(report) => json(report),
+		None => error(404, format!("Report {page_type}/{id} not found")),
+	}
+	.into()
+}
+
+fn archived(inner: &Inner, id: usize, json: bool) -> Route {
+	match inner.archive.as_ref().and_then(|a| a.reports.get(id)) {
+		Some(report) => Route::Archived(Archived {
+			id,
+			path: report.path.clone(),
+			json,
+		}),
+		None => error(404, format!("Archived report {id} not found")).into(),
+	}
+}
+
+fn index(inner: &Inner) -> ReportIndex {
+	let archive = match &inner.archive {
+		Some(archive) => archive
+			.reports
+			.iter()
+			.enumerate()
+			.rev()
+			.map(|(id, report)| {
+				let (top_player, top_damage) = report.top_player.clone().unzip();
+				ReportSummary {
+					kind: "archive",
+					id,
+					map_name: report.map_name.clone(),
+					time: report.time.clone(),
+					top_player,
+					top_damage,
+				}
+			})
+			.collect(),
+		None => Vec::new(),
+	};
+
+	ReportIndex {
+		live: summaries("live", &inner.live_pages),
+		memory: summaries("memory", &inner.memory),
+		archive,
+	}
+}
+
+fn summaries(kind: &'static str, pages: &LruCache<usize, (String, Report)>) -> Vec<ReportSummary> {
+	let mut summaries: Vec<ReportSummary> = pages
+		.iter()
+		.map(|(&id, (_name, report))| {
+			let (top_player, top_damage) = report.top_player().unzip();
+			ReportSummary {
+				kind,
+				id,
+				map_name: report.map_name.clone(),
+				time: report.time.clone(),
+				top_player,
+				top_damage,
+			}
+		})
+		.collect();
+	// lru iterates in most recently used order, not the order they were added
+	summaries.sort_by_key(|s| std::cmp::Reverse(s.id));
+
+	summaries
+}
+
+fn html(page: &impl Template) -> Response<String> {
+	Response::builder()
+		.status(200)
+		.body(page.render().unwrap())
+		.unwrap()
+}
+
+fn json(value: &impl Serialize) -> Response<String> {
+	Response::builder()
+		.status(200)
+		.header("content-type", "application/json")
+		.body(serde_json::to_string(value).unwrap())
+		.unwrap()
+}
+
+fn error(status: u16, message: String) -> Response<String> {
+	Response::builder().status(status).body(message).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::damage_monitor_http_server::{EnemyTab, PlayerRow};
+
+	fn get(inner: &mut Inner, path: &str) -> Response<String> {
+		match route(inner, path) {
+			Route::Ready(response) => response,
+			Route::Archived(archived) => archived.respond(),
+		}
+	}
+
+	fn report(map_name: &str, players: &[(&str, i64)]) -> Report {
+		let row = |&(name, damage): &(&str, i64)| PlayerRow {
+			name: name.to_owned(),
+			is_self: false,
+			status: ' ',
+			damage,
+			damage_percent: String::new(),
+			items: [None, None, None, None],
+			dps: Vec::new(),
+			burst_dps: 0,
+			burst_start: 0,
+			sources: Vec::new(),
+		};
+
+		Report {
+			map_name: map_name.to_owned(),
+			time: "2025-01-01 10:00:00".to_owned(),
+			// the same players on two enemies, the damage is summed
+			enemy_tabs: vec![
+				EnemyTab {
+					name: "Boss".to_owned(),
+					object_id: 1,
+					total_damage: 0,
+					players: players.iter().map(row).collect(),
+					fight_duration: 0,
+					phases: Vec::new(),
+				},
+				EnemyTab {
+					name: "Minion".to_owned(),
+					object_id: 2,
+					total_damage: 0,
+					players: players.iter().map(row).collect(),
+					fight_duration: 0,
+					phases: Vec::new(),
+				},
+			],
+			all_items: Default::default(),
+			all_enemies: Default::default(),
+		}
+	}
+
+	#[test]
+	fn api() {
+		let mut inner = Inner::new(5, None);
+		inner.memory.put(
+			0,
+			(String::new(), report("Shatters", &[("A", 10), ("B", 20)])),
+		);
+		inner
+			.memory
+			.put(1, (String::new(), report("Nest", &[("A", 5)])));
+		inner
+			.live_pages
+			.put(0, (String::new(), report("Nest", &[])));
+
+		let response = get(&mut inner, "api/reports");
+		assert_eq!(response.status(), 200);
+		assert_eq!(response.headers()["content-type"], "application/json");
+		let index: serde_json::Value = serde_json::from_str(response.body()).unwrap();
+		assert_eq!(index["live"][0]["top_player"], serde_json::Value::Null);
+		assert_eq!(index["memory"][0]["map_name"], "Nest");
+		assert_eq!(index["memory"][1]["top_player"], "B");
+		assert_eq!(index["memory"][1]["top_damage"], 40);
+		assert_eq!(index["archive"], serde_json::json!([]));
+
+		let response = get(&mut inner, "api/reports/memory/0");
+		let report: Report = serde_json::from_str(response.body()).unwrap();
+		assert_eq!(report.map_name, "Shatters");
+
+		assert_eq!(get(&mut inner, "api/reports/memory/7").status(), 404);
+		assert_eq!(get(&mut inner, "api/reports/death/0").status(), 400);
+		assert_eq!(get(&mut inner, "api/reports/memory/x").status(), 400);
+
+		assert_eq!(get(&mut inner, "memory/7").status(), 404);
+		assert_eq!(get(&mut inner, "death/0").status(), 404);
+		assert_eq!(get(&mut inner, "archive/0").status(), 404);
+		assert_eq!(get(&mut inner, "memory").status(), 400);
+		assert_eq!(get(&mut inner, "something/0").status(), 400);
+
+		let response = get(&mut inner, "");
+		assert_eq!(response.status(), 200);
+		assert!(response.body().contains(r#"<a href="/memory/1">Nest</a>"#));
+	}
+}
@@ -0,0 +1,216 @@
+//! `rotmguard inspect <packet_data file>` prints a capture made by the packet logger
+//! in a readable form, decoding the packets that we have parsers for.
+
+use crate::{
+	Direction,
+	packet_logger::{self, CapturedPacket},
+	protocol::{
+		Aoe, AoeAck, CreateSuccess, Damage, EnemyHit, EnemyShoot, Escape, GroundDamage, MapInfo,
+		Move, NewTick, Notification, Packet, PlayerHit, PlayerShoot, PlayerText, Reconnect,
+		ServerPlayerShoot, ShowEffect, Update,
+	},
+	proxy::SUPPORTED_BUILD_VERSIONS,
+	util::{PACKET_ID, packet_name},
+};
+use anyhow::{Context, Result};
+use bytes::BytesMut;
+use std::{
+	fmt::Debug,
+	io::{self, Write},
+};
+
+pub const INSPECT_COMMAND: &str = "inspect";
+
+pub fn inspect() -> Result<()> {
+	let path = std::env::args()
+		.nth(2)
+		.context("usage: rotmguard inspect <packet_data file>")?;
+
+	let packets = packet_logger::read_capture(&path).with_context(|| format!("reading {path}"))?;
+
+	print_capture(&packets, io::stdout().lock())?;
+
+	Ok(())
+}
+
+fn print_capture(packets: &[CapturedPacket], mut out: impl Write) -> io::Result<()> {
+	for (i, packet) in packets.iter().enumerate() {
+		let id = packet.data[0];
+		let (direction, name) = match packet.direction {
+			Direction::C2S => ("C2S", packet_name(Direction::C2S, id)),
+			Direction::S2C => ("S2C", packet_name(Direction::S2C, id)),
+		};
+
+		writeln!(
+			out,
+			"#{i} [{:.3}s] {direction} {} ({id}), {} bytes",
+			packet.time.as_secs_f64(),
+			name.unwrap_or("UNKNOWN"),
+			packet.data.len(),
+		)?;
+
+		let cursor = &mut 1;
+		match decode(packet.direction, &packet.data, cursor) {
+			Ok(Some(decoded)) => {
+				for line in decoded.lines() {
+					writeln!(out, "    {line}")?;
+				}
+
+				let leftover = packet.data.len() - *cursor;
+				if leftover > 0 {
+					writeln!(out, "    ({leftover} bytes left unparsed)")?;
+				}
+			}
+			Ok(None) => {} // no parser for this packet
+			Err(e) => {
+				writeln!(out, "    error decoding at byte {cursor}: {e:#}")?;
+			}
+		}
+
+		if (packet.direction, id) == (Direction::S2C, PACKET_ID::S2C_MAPINFO)
+			&& let Ok(map_info) = MapInfo::decode(&packet.data, &mut 1)
+		{
+			let supported = if SUPPORTED_BUILD_VERSIONS.contains(&map_info.build_version) {
+				"supported"
+			} else {
+				"not supported, all packets would be passed through"
+			};
+			writeln!(
+				out,
+				"    (build version {}: {supported})",
+				map_info.build_version
+			)?;
+		}
+	}
+
+	Ok(())
+}
+
+// returns None if we dont know how to decode the packet
+fn decode(direction: Direction, b: &BytesMut, c: &mut usize) -> Result<Option<String>> {
+	fn show<'a, P: Packet<'a> + Debug>(b: &'a BytesMut, c: &mut usize) -> Result<Option<String>> {
+		Ok(Some(format!("{:#?}", P::decode(b, c)?)))
+	}
+
+	match (direction, b[0]) {
+		(Direction::C2S, PACKET_ID::C2S_PLAYERTEXT) => show::<PlayerText>(b, c),
+		(Direction::C2S, PACKET_ID::C2S_MOVE) => show::<Move>(b, c),
+		(Direction::C2S, PACKET_ID::C2S_PLAYERHIT) => show::<PlayerHit>(b, c),
+		(Direction::C2S, PACKET_ID::C2S_AOEACK) => show::<AoeAck>(b, c),
+		(Direction::C2S, PACKET_ID::C2S_GROUNDDAMAGE) => show::<GroundDamage>(b, c),
+		(Direction::C2S, PACKET_ID::C2S_PLAYERSHOOT) => show::<PlayerShoot>(b, c),
+		(Direction::C2S, PACKET_ID::C2S_ENEMYHIT) => show::<EnemyHit>(b, c),
+		(Direction::C2S, PACKET_ID::C2S_ESCAPE) => show::<Escape>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_MAPINFO) => show::<MapInfo>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_UPDATE) => show::<Update>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_NEWTICK) => show::<NewTick>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_CREATE_SUCCESS) => show::<CreateSuccess>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_ENEMYSHOOT) => show::<EnemyShoot>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_NOTIFICATION) => show::<Notification>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_DAMAGE) => show::<Damage>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_AOE) => show::<Aoe>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_SERVERPLAYERSHOOT) => show::<ServerPlayerShoot>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_RECONNECT) => show::<Reconnect>(b, c),
+		(Direction::S2C, PACKET_ID::S2C_SHOWEFFECT) => show::<ShowEffect>(b, c),
+		_ => Ok(None),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::write_str;
+	use bytes::BufMut;
+	use std::time::Duration;
+
+	#[test]
+	fn print() {
+		let mut text = BytesMut::new();
+		text.put_u8(PACKET_ID::C2S_PLAYERTEXT);
+		write_str("/hi", &mut text);
+
+		let mut truncated = BytesMut::new();
+		truncated.put_u8(PACKET_ID::S2C_DAMAGE);
+		truncated.put_u32(5);
+
+		let packets = [
+			CapturedPacket {
+				direction: Direction::C2S,
+				time: Duration::from_millis(1500),
+				data: text,
+			},
+			CapturedPacket {
+				direction: Direction::S2C,
+				time: Duration::from_millis(1600),
+				data: truncated,
+			},
+			CapturedPacket {
+				direction: Direction::S2C,
+				time: Duration::from_millis(1700),
+				data: BytesMut::from(&[PACKET_ID::S2C_PING, 1, 2, 3, 4][..]),
+			},
+		];
+
+		let mut out = Vec::new();
+		print_capture(&packets, &mut out).unwrap();
+
+		assert_eq!(
+			String::from_utf8(out).unwrap(),
+			"\
+#0 [1.500s] C2S PLAYERTEXT (9), 6 bytes
+    PlayerText {
+        text: \"/hi\",
+    }
+#1 [1.600s] S2C DAMAGE (75), 5 bytes
+    error decoding at byte 5: Not enough bytes remaining in buffer to read value (requested 1 but only 0 available)
+#2 [1.700s] S2C PING (8), 5 bytes
+"
+		);
+	}
+
+	#[test]
+	fn build_version() {
+		let map_info = |build_version| CapturedPacket {
+			direction: Direction::S2C,
+			time: Duration::ZERO,
+			data: BytesMut::from(
+				MapInfo {
+					width: 100,
+					height: 100,
+					name: "Nexus",
+					display_name: "Nexus",
+					realm_name: "",
+					seed: 1,
+					background: 0,
+					difficulty: 0.0,
+					allow_teleport: false,
+					no_save: false,
+					show_displays: false,
+					max_players: 85,
+					game_opened_time: 0,
+					build_version,
+					background_color: 0,
+					unknown: &[],
+				}
+				.to_bytes(),
+			),
+		};
+
+		let mut out = Vec::new();
+		print_capture(
+			&[map_info(SUPPORTED_BUILD_VERSIONS[0]), map_info("1.2.3")],
+			&mut out,
+		)
+		.unwrap();
+		let out = String::from_utf8(out).unwrap();
+		assert!(out.contains(&format!(
+			"(build version {}: supported)",
+			SUPPORTED_BUILD_VERSIONS[0]
+		)));
+		assert!(
+			out.contains(
+				"(build version 1.2.3: not supported, all packets would be passed through)"
+			)
+		);
+	}
+}
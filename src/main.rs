@@ -8,15 +8,17 @@ use std::sync::Arc;
 use std::{env, fs};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::select;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 mod assets;
 mod config;
 mod damage_monitor_http_server;
 mod fetch_server_list;
+mod inspect;
 mod iptables;
 mod logging;
 mod packet_logger;
+mod protocol;
 mod proxy;
 mod rc4;
 mod stats;
@@ -31,7 +33,7 @@ struct Rotmguard {
 }
 
 // used in many places..
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 enum Direction {
 	C2S,
 	S2C,
@@ -42,6 +44,9 @@ fn main() -> Result<()> {
 		if arg == iptables::IPTABLES_ACTOR_FLAG {
 			return iptables::iptables_actor();
 		}
+		if arg == inspect::INSPECT_COMMAND {
+			return inspect::inspect();
+		}
 	}
 
 	// Initialize config
@@ -78,6 +83,13 @@ async fn async_main(config: Config, assets: Assets) -> Result<()> {
 		stats: Default::default(),
 		damage_monitor_http,
 	});
+	if let Err(e) = rotmguard
+		.config
+		.settings
+		.check_servers(&rotmguard.rotmg_servers)
+	{
+		warn!("{e}");
+	}
 
 	select! {
 		res = server(rotmguard) => res,
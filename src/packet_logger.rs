@@ -1,6 +1,7 @@
 use crate::Direction;
-use anyhow::Result;
-use std::env;
+use anyhow::{Result, bail};
+use bytes::{Buf, BytesMut};
+use std::{env, path::Path, time::Duration};
 use tokio::{
 	fs::File,
 	io::{AsyncWriteExt, BufWriter},
@@ -68,3 +69,55 @@ impl PacketLogger {
 		Ok(())
 	}
 }
+
+/// A single packet read back from a capture made by the [`PacketLogger`]
+pub struct CapturedPacket {
+	pub direction: Direction,
+	/// time since the start of logging
+	pub time: Duration,
+	/// the packet itself (first byte is the packet id)
+	pub data: BytesMut,
+}
+
+/// Reads all packets of a `packet_data-*` capture file
+pub fn read_capture(path: impl AsRef<Path>) -> Result<Vec<CapturedPacket>> {
+	let data = std::fs::read(path)?;
+
+	parse_capture(&data)
+}
+
+pub fn parse_capture(mut data: &[u8]) -> Result<Vec<CapturedPacket>> {
+	let mut packets = Vec::new();
+
+	while data.has_remaining() {
+		let i = packets.len();
+
+		let direction = match data.try_get_u8()? {
+			b'c' => Direction::C2S,
+			b's' => Direction::S2C,
+			other => bail!("invalid direction byte {other:#x} at packet {i}"),
+		};
+		let nanos = data.try_get_u128_le()?;
+		let time = Duration::new(
+			(nanos / 1_000_000_000) as u64,
+			(nanos % 1_000_000_000) as u32,
+		);
+		let len = data.try_get_u32_le()? as usize;
+
+		if data.remaining() < len {
+			bail!("capture truncated at packet {i}: expected {len} bytes");
+		}
+		if len == 0 {
+			bail!("empty packet at packet {i}");
+		}
+
+		packets.push(CapturedPacket {
+			direction,
+			time,
+			data: BytesMut::from(&data[..len]),
+		});
+		data.advance(len);
+	}
+
+	Ok(packets)
+}
@@ -0,0 +1,414 @@
+//! Typed model of the packets we care about.
+//!
+//! Decoding a packet and encoding it again gives back the exact same bytes (as long as
+//! all varints were written in their shortest form), so packets can be modified
+//! or built from scratch just by working with the fields.
+
+use anyhow::Result;
+use bytes::{BufMut, Bytes, BytesMut};
+
+mod aoe;
+mod aoeack;
+mod create_success;
+mod damage;
+mod enemyhit;
+mod enemyshoot;
+mod escape;
+mod ground_damage;
+mod mapinfo;
+mod r#move;
+mod newtick;
+mod notification;
+mod object;
+mod playerhit;
+mod playershoot;
+mod playertext;
+mod reconnect;
+mod serverplayershoot;
+mod showeffect;
+mod update;
+
+pub use aoe::Aoe;
+pub use aoeack::AoeAck;
+pub use create_success::CreateSuccess;
+pub use damage::Damage;
+pub use enemyhit::EnemyHit;
+pub use enemyshoot::EnemyShoot;
+pub use escape::Escape;
+pub use ground_damage::GroundDamage;
+pub use mapinfo::MapInfo;
+pub use r#move::{Move, MoveRecord};
+pub use newtick::NewTick;
+pub use notification::{Notification, NotificationKind};
+pub use object::{ObjectStatus, StatData};
+pub use playerhit::PlayerHit;
+pub use playershoot::PlayerShoot;
+pub use playertext::PlayerText;
+pub use reconnect::Reconnect;
+pub use serverplayershoot::ServerPlayerShoot;
+pub use showeffect::ShowEffect;
+#[allow(unused_imports)] // NewObject is not needed outside of decoding yet
+pub use update::{NewObject, Tile, Update};
+
+pub trait Packet<'a>: Sized {
+	const ID: u8;
+
+	/// Decodes the packet, the cursor must be right after the packet id
+	fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self>;
+	/// Encodes the packet without the packet id
+	fn encode(&self, out: &mut BytesMut);
+
+	/// The whole packet, ready to be sent
+	fn to_bytes(&self) -> Bytes {
+		let mut buf = BytesMut::new();
+
+		buf.put_u8(Self::ID);
+		self.encode(&mut buf);
+
+		buf.freeze()
+	}
+}
+
+/// Generates an enum of packets with decoding by packet id
+macro_rules! packet_enum {
+	($(#[$meta:meta])* $name:ident<$lt:lifetime> { $($variant:ident($ty:ty)),* $(,)? }) => {
+		$(#[$meta])*
+		#[derive(Debug, Clone, PartialEq)]
+		pub enum $name<$lt> {
+			$($variant($ty)),*
+		}
+
+		impl<$lt> $name<$lt> {
+			/// Decodes the packet, the cursor must be right after the packet id.
+			/// Returns None if it's not one of the packets in this enum
+			pub fn decode(packet_id: u8, b: &$lt BytesMut, c: &mut usize) -> Result<Option<Self>> {
+				$(
+					if packet_id == <$ty as Packet>::ID {
+						return Ok(Some(Self::$variant(Packet::decode(b, c)?)));
+					}
+				)*
+
+				Ok(None)
+			}
+			/// The whole packet, ready to be sent
+			pub fn to_bytes(&self) -> Bytes {
+				match self {
+					$(Self::$variant(packet) => packet.to_bytes()),*
+				}
+			}
+		}
+	};
+}
+
+packet_enum! {
+	/// All client to server packets that the proxy looks at
+	C2SPacket<'a> {
+		PlayerText(PlayerText<'a>),
+		Move(Move),
+		PlayerHit(PlayerHit),
+		AoeAck(AoeAck),
+		GroundDamage(GroundDamage),
+		PlayerShoot(PlayerShoot),
+		EnemyHit(EnemyHit),
+	}
+}
+
+packet_enum! {
+	/// All server to client packets that the proxy looks at
+	S2CPacket<'a> {
+		MapInfo(MapInfo<'a>),
+		Update(Update<'a>),
+		NewTick(NewTick<'a>),
+		CreateSuccess(CreateSuccess<'a>),
+		EnemyShoot(EnemyShoot),
+		Notification(Notification<'a>),
+		Damage(Damage),
+		Aoe(Aoe),
+		ServerPlayerShoot(ServerPlayerShoot),
+		Reconnect(Reconnect<'a>),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::View;
+	use either::Either;
+
+	// decodes into the same packet type as `_like`
+	fn decode_like<'a, P: Packet<'a>>(_like: &P, b: &'a BytesMut, c: &mut usize) -> Result<P> {
+		P::decode(b, c)
+	}
+
+	// encodes, decodes and encodes again, checking that nothing changed
+	macro_rules! round_trip {
+		($packet:expr $(,)?) => {{
+			let packet = $packet;
+			let buf = BytesMut::from(packet.to_bytes());
+
+			let c = &mut 1;
+			let decoded = decode_like(&packet, &buf, c).unwrap();
+			assert_eq!(*c, buf.len(), "{decoded:?} not fully decoded");
+			assert_eq!(
+				&decoded.to_bytes()[..],
+				&buf[..],
+				"{packet:?} != {decoded:?}"
+			);
+
+			// and reading a truncated packet fails gracefully
+			for len in 1..buf.len() {
+				let truncated = BytesMut::from(&buf[..len]);
+				let _ = decode_like(&packet, &truncated, &mut 1);
+			}
+		}};
+	}
+
+	fn status(object_id: u32) -> ObjectStatus<'static> {
+		ObjectStatus {
+			object_id,
+			pos_x: 12.5,
+			pos_y: -3.25,
+			stats: vec![
+				StatData {
+					stat_type: 1,
+					data: Either::Right(-600),
+					secondary: -1,
+				},
+				StatData {
+					stat_type: 31,
+					data: Either::Left("Player,abc"),
+					secondary: 0,
+				},
+				StatData {
+					stat_type: 29,
+					data: Either::Right(i64::MAX),
+					secondary: 123456,
+				},
+			],
+		}
+	}
+
+	#[test]
+	fn c2s_round_trip() {
+		round_trip!(PlayerText { text: "/hi там" });
+		round_trip!(Move {
+			tick_id: 5,
+			time: 1000,
+			records: vec![
+				MoveRecord {
+					time: 990,
+					pos_x: 1.0,
+					pos_y: 2.0,
+				},
+				MoveRecord {
+					time: 995,
+					pos_x: 1.5,
+					pos_y: 2.5,
+				},
+			],
+		},);
+		round_trip!(PlayerHit {
+			bullet_id: 7,
+			owner_id: 8,
+		},);
+		round_trip!(AoeAck {
+			time: 1,
+			pos_x: 2.0,
+			pos_y: 3.0,
+		},);
+		round_trip!(GroundDamage {
+			time: 1,
+			pos_x: 2.0,
+			pos_y: 3.0,
+		},);
+		round_trip!(PlayerShoot {
+			time: 1,
+			bullet_id: 2,
+			weapon_id: 3,
+			projectile_type: 255,
+			pos_x: 4.0,
+			pos_y: 5.0,
+			angle: 6.0,
+			is_burst: true,
+			pattern: 7,
+			attack_type: 8,
+			player_pos_x: 9.0,
+			player_pos_y: 10.0,
+		},);
+		round_trip!(EnemyHit {
+			time: 1,
+			bullet_id: 2,
+			shooter_id: 3,
+			target_id: 4,
+			is_killing: false,
+			unknown: 5,
+		},);
+		round_trip!(Escape);
+	}
+
+	#[test]
+	fn s2c_round_trip() {
+		round_trip!(MapInfo {
+			width: 256,
+			height: 256,
+			name: "Nexus",
+			display_name: "{s.nexus}",
+			realm_name: "",
+			seed: 0xdeadbeef,
+			background: 0,
+			difficulty: 1.5,
+			allow_teleport: true,
+			no_save: false,
+			show_displays: true,
+			max_players: 85,
+			game_opened_time: 0,
+			build_version: "1.2.3.4",
+			background_color: 0x123456,
+			unknown: &[1, 2, 3],
+		},);
+		round_trip!(Update {
+			pos_x: 1.0,
+			pos_y: 2.0,
+			level_type: 3,
+			tiles: vec![Tile {
+				x: -1,
+				y: 5,
+				tile_type: 0x1234,
+			}],
+			new_objects: vec![NewObject {
+				object_type: 0x300,
+				status: status(100),
+			}],
+			to_remove: vec![1, 200, 30000],
+		},);
+		round_trip!(NewTick {
+			tick_id: 1,
+			tick_time: 200,
+			real_time_ms: 3,
+			last_real_time_ms: 4,
+			statuses: vec![status(1), status(1_000_000)],
+		},);
+		round_trip!(CreateSuccess {
+			object_id: 1,
+			char_id: 2,
+			unknown: "?",
+		},);
+		for multishot in [None, Some((3, 0.5))] {
+			round_trip!(EnemyShoot {
+				bullet_id: 1,
+				owner_id: 2,
+				bullet_type: 3,
+				pos_x: 4.0,
+				pos_y: 5.0,
+				angle: 6.0,
+				damage: -7,
+				multishot,
+			},);
+		}
+		for kind in [
+			NotificationKind::ObjectText {
+				message: "{\"k\":\"s.plus_symbol\",\"t\":{\"amount\":\"50\"}}",
+				object_id: 1,
+				color: 0x00ff00,
+			},
+			NotificationKind::PlayerDeath {
+				json: "{}",
+				picture_type: 2,
+			},
+			NotificationKind::Text {
+				text: "hi :)",
+				unknown: 0,
+				color: 0x8888ff,
+			},
+			NotificationKind::Other {
+				notification_type: 99,
+				data: &[1, 2, 3, 4],
+			},
+		] {
+			round_trip!(Notification { extra: 1, kind });
+		}
+		round_trip!(Damage {
+			target_id: 1,
+			effects: vec![2, 3],
+			damage: 4,
+			damage_properties: 5,
+			bullet_id: 6,
+			owner_id: 7,
+		},);
+		round_trip!(Aoe {
+			pos_x: 1.0,
+			pos_y: 2.0,
+			radius: 3.0,
+			damage: 4,
+			effect: 5,
+			duration: 6.0,
+			orig_type: 7,
+			color: 8,
+			armor_piercing: true,
+		},);
+		for (bullet_type, bullet_count, angle_between_bullets) in [
+			(None, None, None),
+			(Some(1), None, None),
+			(Some(1), Some(2), Some(3.0)),
+		] {
+			round_trip!(ServerPlayerShoot {
+				bullet_id: 1,
+				shooter_id: 2,
+				unknown: 3,
+				pos_x: 4.0,
+				pos_y: 5.0,
+				angle: 6.0,
+				damage: 7,
+				summoner_id: 8,
+				bullet_type,
+				bullet_count,
+				angle_between_bullets,
+			},);
+		}
+		round_trip!(Reconnect {
+			hostname: "have fun :)",
+			address: "127.0.0.1",
+			port: 2050,
+			game_id: 0xfffffffe,
+			key_time: 0xffffffff,
+			key: &[1, 2, 3],
+		},);
+		for object_id in [None, Some(1234)] {
+			round_trip!(ShowEffect {
+				effect_type: 18,
+				object_id,
+				pos1_x: Some(1.0),
+				pos1_y: Some(2.0),
+				pos2_x: None,
+				pos2_y: Some(4.0),
+				color: Some(0xffffff),
+				duration: None,
+			},);
+		}
+	}
+
+	#[test]
+	fn decodes_original_bytes() {
+		// hand written the way the old create_notification did
+		let mut buf = BytesMut::new();
+		buf.put_u8(crate::util::PACKET_ID::S2C_NOTIFICATION);
+		buf.put_u8(12);
+		buf.put_u8(0);
+		crate::util::write_str("hello", &mut buf);
+		buf.put_u32(0);
+		buf.put_u32(0xff8888);
+
+		let c = &mut 1;
+		let notification = Notification::decode(&buf, c).unwrap();
+		assert_eq!(View(&buf, c).slice(), &[] as &[u8]);
+		assert_eq!(
+			notification.kind,
+			NotificationKind::Text {
+				text: "hello",
+				unknown: 0,
+				color: 0xff8888
+			}
+		);
+		assert_eq!(notification.to_bytes(), buf);
+	}
+}
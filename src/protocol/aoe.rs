@@ -0,0 +1,46 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aoe {
+	pub pos_x: f32,
+	pub pos_y: f32,
+	pub radius: f32,
+	pub damage: u16,
+	pub effect: u8,
+	pub duration: f32,
+	pub orig_type: u16,
+	pub color: u32,
+	pub armor_piercing: bool,
+}
+
+impl Packet<'_> for Aoe {
+	const ID: u8 = PACKET_ID::S2C_AOE;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			pos_x: View(b, c).try_get_f32()?,
+			pos_y: View(b, c).try_get_f32()?,
+			radius: View(b, c).try_get_f32()?,
+			damage: View(b, c).try_get_u16()?,
+			effect: View(b, c).try_get_u8()?,
+			duration: View(b, c).try_get_f32()?,
+			orig_type: View(b, c).try_get_u16()?,
+			color: View(b, c).try_get_u32()?,
+			armor_piercing: View(b, c).try_get_u8()? != 0,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_f32(self.pos_x);
+		out.put_f32(self.pos_y);
+		out.put_f32(self.radius);
+		out.put_u16(self.damage);
+		out.put_u8(self.effect);
+		out.put_f32(self.duration);
+		out.put_u16(self.orig_type);
+		out.put_u32(self.color);
+		out.put_u8(self.armor_piercing as u8);
+	}
+}
@@ -0,0 +1,29 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View, read_str, write_str};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+/// Sent when joining to tell the client its object id
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateSuccess<'a> {
+	pub object_id: u32,
+	pub char_id: u32,
+	pub unknown: &'a str,
+}
+
+impl<'a> Packet<'a> for CreateSuccess<'a> {
+	const ID: u8 = PACKET_ID::S2C_CREATE_SUCCESS;
+
+	fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			object_id: View(b, c).try_get_u32()?,
+			char_id: View(b, c).try_get_u32()?,
+			unknown: read_str(View(b, c))?,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u32(self.object_id);
+		out.put_u32(self.char_id);
+		write_str(self.unknown, &mut *out);
+	}
+}
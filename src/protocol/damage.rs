@@ -0,0 +1,46 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+/// Tells the client about damage done to other players and enemies
+#[derive(Debug, Clone, PartialEq)]
+pub struct Damage {
+	pub target_id: u32,
+	pub effects: Vec<u8>,
+	pub damage: u16,
+	pub damage_properties: u8,
+	pub bullet_id: u16,
+	pub owner_id: u32,
+}
+
+impl Packet<'_> for Damage {
+	const ID: u8 = PACKET_ID::S2C_DAMAGE;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		let target_id = View(b, c).try_get_u32()?;
+		let effects_n = View(b, c).try_get_u8()?;
+		let mut effects = Vec::with_capacity(effects_n as usize);
+		for _ in 0..effects_n {
+			effects.push(View(b, c).try_get_u8()?);
+		}
+
+		Ok(Self {
+			target_id,
+			effects,
+			damage: View(b, c).try_get_u16()?,
+			damage_properties: View(b, c).try_get_u8()?,
+			bullet_id: View(b, c).try_get_u16()?,
+			owner_id: View(b, c).try_get_u32()?,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u32(self.target_id);
+		out.put_u8(self.effects.len() as u8);
+		out.put_slice(&self.effects);
+		out.put_u16(self.damage);
+		out.put_u8(self.damage_properties);
+		out.put_u16(self.bullet_id);
+		out.put_u32(self.owner_id);
+	}
+}
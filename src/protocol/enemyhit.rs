@@ -0,0 +1,37 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnemyHit {
+	pub time: u32,
+	pub bullet_id: u16,
+	pub shooter_id: u32,
+	pub target_id: u32,
+	pub is_killing: bool,
+	pub unknown: u32,
+}
+
+impl Packet<'_> for EnemyHit {
+	const ID: u8 = PACKET_ID::C2S_ENEMYHIT;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			time: View(b, c).try_get_u32()?,
+			bullet_id: View(b, c).try_get_u16()?,
+			shooter_id: View(b, c).try_get_u32()?,
+			target_id: View(b, c).try_get_u32()?,
+			is_killing: View(b, c).try_get_u8()? != 0,
+			unknown: View(b, c).try_get_u32()?,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u32(self.time);
+		out.put_u16(self.bullet_id);
+		out.put_u32(self.shooter_id);
+		out.put_u32(self.target_id);
+		out.put_u8(self.is_killing as u8);
+		out.put_u32(self.unknown);
+	}
+}
@@ -0,0 +1,51 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnemyShoot {
+	pub bullet_id: u16,
+	pub owner_id: u32,
+	pub bullet_type: u8,
+	pub pos_x: f32,
+	pub pos_y: f32,
+	pub angle: f32,
+	pub damage: i16,
+	/// (number of shots, angle between shots), not sent for single shots
+	pub multishot: Option<(u8, f32)>,
+}
+
+impl Packet<'_> for EnemyShoot {
+	const ID: u8 = PACKET_ID::S2C_ENEMYSHOOT;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			bullet_id: View(b, c).try_get_u16()?,
+			owner_id: View(b, c).try_get_u32()?,
+			bullet_type: View(b, c).try_get_u8()?,
+			pos_x: View(b, c).try_get_f32()?,
+			pos_y: View(b, c).try_get_f32()?,
+			angle: View(b, c).try_get_f32()?,
+			damage: View(b, c).try_get_i16()?,
+			multishot: if View(b, c).has_remaining() {
+				Some((View(b, c).try_get_u8()?, View(b, c).try_get_f32()?))
+			} else {
+				None
+			},
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u16(self.bullet_id);
+		out.put_u32(self.owner_id);
+		out.put_u8(self.bullet_type);
+		out.put_f32(self.pos_x);
+		out.put_f32(self.pos_y);
+		out.put_f32(self.angle);
+		out.put_i16(self.damage);
+		if let Some((numshots, angle_between_shots)) = self.multishot {
+			out.put_u8(numshots);
+			out.put_f32(angle_between_shots);
+		}
+	}
+}
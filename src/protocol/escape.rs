@@ -0,0 +1,17 @@
+use super::Packet;
+use crate::util::PACKET_ID;
+use anyhow::Result;
+use bytes::BytesMut;
+
+/// Sends the player to the nexus
+#[derive(Debug, Clone, PartialEq)]
+pub struct Escape;
+
+impl Packet<'_> for Escape {
+	const ID: u8 = PACKET_ID::C2S_ESCAPE;
+
+	fn decode(_b: &BytesMut, _c: &mut usize) -> Result<Self> {
+		Ok(Self)
+	}
+	fn encode(&self, _out: &mut BytesMut) {}
+}
@@ -0,0 +1,28 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroundDamage {
+	pub time: u32,
+	pub pos_x: f32,
+	pub pos_y: f32,
+}
+
+impl Packet<'_> for GroundDamage {
+	const ID: u8 = PACKET_ID::C2S_GROUNDDAMAGE;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			time: View(b, c).try_get_u32()?,
+			pos_x: View(b, c).try_get_f32()?,
+			pos_y: View(b, c).try_get_f32()?,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u32(self.time);
+		out.put_f32(self.pos_x);
+		out.put_f32(self.pos_y);
+	}
+}
@@ -0,0 +1,72 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View, read_str, write_str};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapInfo<'a> {
+	pub width: u32,
+	pub height: u32,
+	pub name: &'a str,
+	pub display_name: &'a str,
+	pub realm_name: &'a str,
+	pub seed: u32,
+	pub background: u32, // ? this is not the color tho...
+	pub difficulty: f32,
+	pub allow_teleport: bool,
+	pub no_save: bool,       // ?
+	pub show_displays: bool, // ?
+	pub max_players: u16,
+	pub game_opened_time: u32,
+	pub build_version: &'a str,
+	pub background_color: u32,
+	/// more data but format is not really known
+	pub unknown: &'a [u8],
+}
+
+impl<'a> Packet<'a> for MapInfo<'a> {
+	const ID: u8 = PACKET_ID::S2C_MAPINFO;
+
+	fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			width: View(b, c).try_get_u32()?,
+			height: View(b, c).try_get_u32()?,
+			name: read_str(View(b, c))?,
+			display_name: read_str(View(b, c))?,
+			realm_name: read_str(View(b, c))?,
+			seed: View(b, c).try_get_u32()?,
+			background: View(b, c).try_get_u32()?,
+			difficulty: View(b, c).try_get_f32()?,
+			allow_teleport: View(b, c).try_get_u8()? != 0,
+			no_save: View(b, c).try_get_u8()? != 0,
+			show_displays: View(b, c).try_get_u8()? != 0,
+			max_players: View(b, c).try_get_u16()?,
+			game_opened_time: View(b, c).try_get_u32()?,
+			build_version: read_str(View(b, c))?,
+			background_color: View(b, c).try_get_u32()?,
+			unknown: {
+				let rest = View(b, c).slice();
+				*c = b.len();
+				rest
+			},
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u32(self.width);
+		out.put_u32(self.height);
+		write_str(self.name, &mut *out);
+		write_str(self.display_name, &mut *out);
+		write_str(self.realm_name, &mut *out);
+		out.put_u32(self.seed);
+		out.put_u32(self.background);
+		out.put_f32(self.difficulty);
+		out.put_u8(self.allow_teleport as u8);
+		out.put_u8(self.no_save as u8);
+		out.put_u8(self.show_displays as u8);
+		out.put_u16(self.max_players);
+		out.put_u32(self.game_opened_time);
+		write_str(self.build_version, &mut *out);
+		out.put_u32(self.background_color);
+		out.put_slice(self.unknown);
+	}
+}
@@ -0,0 +1,53 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Move {
+	pub tick_id: u32,
+	pub time: u32,
+	pub records: Vec<MoveRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveRecord {
+	pub time: u32,
+	pub pos_x: f32,
+	pub pos_y: f32,
+}
+
+impl Packet<'_> for Move {
+	const ID: u8 = PACKET_ID::C2S_MOVE;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		let tick_id = View(b, c).try_get_u32()?;
+		let time = View(b, c).try_get_u32()?;
+		let records_n = View(b, c).try_get_u16()?;
+
+		let mut records = Vec::with_capacity(records_n as usize);
+		for _ in 0..records_n {
+			records.push(MoveRecord {
+				time: View(b, c).try_get_u32()?,
+				pos_x: View(b, c).try_get_f32()?,
+				pos_y: View(b, c).try_get_f32()?,
+			});
+		}
+
+		Ok(Self {
+			tick_id,
+			time,
+			records,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u32(self.tick_id);
+		out.put_u32(self.time);
+		out.put_u16(self.records.len() as u16);
+		for record in &self.records {
+			out.put_u32(record.time);
+			out.put_f32(record.pos_x);
+			out.put_f32(record.pos_y);
+		}
+	}
+}
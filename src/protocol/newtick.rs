@@ -0,0 +1,48 @@
+use super::{ObjectStatus, Packet};
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewTick<'a> {
+	pub tick_id: u32,
+	pub tick_time: u32,
+	pub real_time_ms: u32,
+	pub last_real_time_ms: u16,
+	pub statuses: Vec<ObjectStatus<'a>>,
+}
+
+impl<'a> Packet<'a> for NewTick<'a> {
+	const ID: u8 = PACKET_ID::S2C_NEWTICK;
+
+	fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self> {
+		let tick_id = View(b, c).try_get_u32()?;
+		let tick_time = View(b, c).try_get_u32()?;
+		let real_time_ms = View(b, c).try_get_u32()?;
+		let last_real_time_ms = View(b, c).try_get_u16()?;
+
+		let statuses_n = View(b, c).try_get_u16()?;
+		let mut statuses = Vec::with_capacity(statuses_n as usize);
+		for _ in 0..statuses_n {
+			statuses.push(ObjectStatus::decode(b, c)?);
+		}
+
+		Ok(Self {
+			tick_id,
+			tick_time,
+			real_time_ms,
+			last_real_time_ms,
+			statuses,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u32(self.tick_id);
+		out.put_u32(self.tick_time);
+		out.put_u32(self.real_time_ms);
+		out.put_u16(self.last_real_time_ms);
+		out.put_u16(self.statuses.len() as u16);
+		for status in &self.statuses {
+			status.encode(out);
+		}
+	}
+}
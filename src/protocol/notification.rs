@@ -0,0 +1,120 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View, read_str, write_str};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification<'a> {
+	pub extra: u8, // who knows 💀
+	pub kind: NotificationKind<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationKind<'a> {
+	/// Text above an object, heals for example
+	ObjectText {
+		message: &'a str,
+		object_id: u32,
+		color: u32,
+	},
+	PlayerDeath {
+		json: &'a str,
+		picture_type: u32,
+	},
+	/// Plain text in the middle of the screen
+	Text {
+		text: &'a str,
+		unknown: u32,
+		color: u32,
+	},
+	/// Format not known
+	Other {
+		notification_type: u8,
+		data: &'a [u8],
+	},
+}
+
+impl NotificationKind<'_> {
+	fn notification_type(&self) -> u8 {
+		match self {
+			NotificationKind::ObjectText { .. } => 6,
+			NotificationKind::PlayerDeath { .. } => 7,
+			NotificationKind::Text { .. } => 12,
+			NotificationKind::Other {
+				notification_type, ..
+			} => *notification_type,
+		}
+	}
+}
+
+impl<'a> Packet<'a> for Notification<'a> {
+	const ID: u8 = PACKET_ID::S2C_NOTIFICATION;
+
+	fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self> {
+		let notification_type = View(b, c).try_get_u8()?;
+		let extra = View(b, c).try_get_u8()?;
+
+		let kind = match notification_type {
+			6 => NotificationKind::ObjectText {
+				message: read_str(View(b, c))?,
+				object_id: View(b, c).try_get_u32()?,
+				color: View(b, c).try_get_u32()?,
+			},
+			7 => NotificationKind::PlayerDeath {
+				json: read_str(View(b, c))?,
+				picture_type: View(b, c).try_get_u32()?,
+			},
+			12 => NotificationKind::Text {
+				text: read_str(View(b, c))?,
+				unknown: View(b, c).try_get_u32()?,
+				color: View(b, c).try_get_u32()?,
+			},
+			_ => {
+				let data = View(b, c).slice();
+				*c = b.len();
+
+				NotificationKind::Other {
+					notification_type,
+					data,
+				}
+			}
+		};
+
+		Ok(Self { extra, kind })
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u8(self.kind.notification_type());
+		out.put_u8(self.extra);
+
+		match self.kind {
+			NotificationKind::ObjectText {
+				message,
+				object_id,
+				color,
+			} => {
+				write_str(message, &mut *out);
+				out.put_u32(object_id);
+				out.put_u32(color);
+			}
+			NotificationKind::PlayerDeath { json, picture_type } => {
+				write_str(json, &mut *out);
+				out.put_u32(picture_type);
+			}
+			NotificationKind::Text {
+				text,
+				unknown,
+				color,
+			} => {
+				write_str(text, &mut *out);
+				out.put_u32(unknown);
+				out.put_u32(color);
+			}
+			NotificationKind::Other {
+				notification_type: _,
+				data,
+			} => {
+				out.put_slice(data);
+			}
+		}
+	}
+}
@@ -0,0 +1,73 @@
+use crate::util::{
+	OBJECT_STR_STATS, View, read_compressed_int, read_str, write_compressed_int, write_str,
+};
+use anyhow::{Result, bail};
+use bytes::{Buf, BufMut, BytesMut};
+use either::Either;
+
+/// Object id, position and stats, as found in Update and NewTick
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectStatus<'a> {
+	pub object_id: u32,
+	pub pos_x: f32,
+	pub pos_y: f32,
+	pub stats: Vec<StatData<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatData<'a> {
+	pub stat_type: u8,
+	pub data: Either<&'a str, i64>,
+	pub secondary: i64,
+}
+
+impl<'a> ObjectStatus<'a> {
+	pub fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self> {
+		let object_id = read_compressed_int(View(b, c))? as u32;
+		let pos_x = View(b, c).try_get_f32()?;
+		let pos_y = View(b, c).try_get_f32()?;
+
+		let stats_n = read_compressed_int(View(b, c))?;
+		if stats_n < 0 {
+			bail!("negative stat count {stats_n}");
+		}
+		let mut stats = Vec::new();
+		for _ in 0..stats_n {
+			let stat_type = View(b, c).try_get_u8()?;
+			let data = if OBJECT_STR_STATS.contains(&stat_type) {
+				Either::Left(read_str(View(b, c))?)
+			} else {
+				Either::Right(read_compressed_int(View(b, c))?)
+			};
+			let secondary = read_compressed_int(View(b, c))?;
+
+			stats.push(StatData {
+				stat_type,
+				data,
+				secondary,
+			});
+		}
+
+		Ok(Self {
+			object_id,
+			pos_x,
+			pos_y,
+			stats,
+		})
+	}
+	pub fn encode(&self, out: &mut BytesMut) {
+		write_compressed_int(self.object_id as i64, &mut *out);
+		out.put_f32(self.pos_x);
+		out.put_f32(self.pos_y);
+
+		write_compressed_int(self.stats.len() as i64, &mut *out);
+		for stat in &self.stats {
+			out.put_u8(stat.stat_type);
+			match stat.data {
+				Either::Left(s) => write_str(s, &mut *out),
+				Either::Right(i) => write_compressed_int(i, &mut *out),
+			}
+			write_compressed_int(stat.secondary, &mut *out);
+		}
+	}
+}
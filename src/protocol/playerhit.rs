@@ -0,0 +1,25 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerHit {
+	pub bullet_id: u16,
+	pub owner_id: u32,
+}
+
+impl Packet<'_> for PlayerHit {
+	const ID: u8 = PACKET_ID::C2S_PLAYERHIT;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			bullet_id: View(b, c).try_get_u16()?,
+			owner_id: View(b, c).try_get_u32()?,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u16(self.bullet_id);
+		out.put_u32(self.owner_id);
+	}
+}
@@ -0,0 +1,55 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerShoot {
+	pub time: u32,
+	pub bullet_id: u16,
+	pub weapon_id: u16,
+	pub projectile_type: u8,
+	pub pos_x: f32,
+	pub pos_y: f32,
+	pub angle: f32,
+	pub is_burst: bool,
+	pub pattern: u8,
+	pub attack_type: u8,
+	pub player_pos_x: f32,
+	pub player_pos_y: f32,
+}
+
+impl Packet<'_> for PlayerShoot {
+	const ID: u8 = PACKET_ID::C2S_PLAYERSHOOT;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			time: View(b, c).try_get_u32()?,
+			bullet_id: View(b, c).try_get_u16()?,
+			weapon_id: View(b, c).try_get_u16()?,
+			projectile_type: View(b, c).try_get_u8()?,
+			pos_x: View(b, c).try_get_f32()?,
+			pos_y: View(b, c).try_get_f32()?,
+			angle: View(b, c).try_get_f32()?,
+			is_burst: View(b, c).try_get_u8()? != 0,
+			pattern: View(b, c).try_get_u8()?,
+			attack_type: View(b, c).try_get_u8()?,
+			player_pos_x: View(b, c).try_get_f32()?,
+			player_pos_y: View(b, c).try_get_f32()?,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u32(self.time);
+		out.put_u16(self.bullet_id);
+		out.put_u16(self.weapon_id);
+		out.put_u8(self.projectile_type);
+		out.put_f32(self.pos_x);
+		out.put_f32(self.pos_y);
+		out.put_f32(self.angle);
+		out.put_u8(self.is_burst as u8);
+		out.put_u8(self.pattern);
+		out.put_u8(self.attack_type);
+		out.put_f32(self.player_pos_x);
+		out.put_f32(self.player_pos_y);
+	}
+}
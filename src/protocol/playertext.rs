@@ -0,0 +1,22 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View, read_str, write_str};
+use anyhow::Result;
+use bytes::BytesMut;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerText<'a> {
+	pub text: &'a str,
+}
+
+impl<'a> Packet<'a> for PlayerText<'a> {
+	const ID: u8 = PACKET_ID::C2S_PLAYERTEXT;
+
+	fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			text: read_str(View(b, c))?,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		write_str(self.text, out);
+	}
+}
@@ -0,0 +1,51 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View, read_str, write_str};
+use anyhow::{Result, bail};
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconnect<'a> {
+	pub hostname: &'a str,
+	pub address: &'a str,
+	pub port: u16,
+	pub game_id: u32,
+	pub key_time: u32,
+	pub key: &'a [u8],
+}
+
+impl<'a> Packet<'a> for Reconnect<'a> {
+	const ID: u8 = PACKET_ID::S2C_RECONNECT;
+
+	fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self> {
+		let hostname = read_str(View(b, c))?;
+		let address = read_str(View(b, c))?;
+		let port = View(b, c).try_get_u16()?;
+		let game_id = View(b, c).try_get_u32()?;
+		let key_time = View(b, c).try_get_u32()?;
+
+		let key_len = View(b, c).try_get_u16()? as usize;
+		if View(b, c).remaining() < key_len {
+			bail!("not enough bytes to fit {key_len} long key");
+		}
+		let key = &View(b, c).slice()[..key_len];
+		View(b, c).advance(key_len);
+
+		Ok(Self {
+			hostname,
+			address,
+			port,
+			game_id,
+			key_time,
+			key,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		write_str(self.hostname, &mut *out);
+		write_str(self.address, &mut *out);
+		out.put_u16(self.port);
+		out.put_u32(self.game_id);
+		out.put_u32(self.key_time);
+		out.put_u16(self.key.len() as u16);
+		out.put_slice(self.key);
+	}
+}
@@ -0,0 +1,59 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View};
+use anyhow::Result;
+use bytes::{Buf, BufMut, BytesMut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerPlayerShoot {
+	pub bullet_id: u16,
+	pub shooter_id: u32,
+	pub unknown: u32,
+	pub pos_x: f32,
+	pub pos_y: f32,
+	pub angle: f32,
+	pub damage: u16,
+	pub summoner_id: u32,
+	// these are not always sent
+	pub bullet_type: Option<u8>,
+	pub bullet_count: Option<u8>,
+	pub angle_between_bullets: Option<f32>,
+}
+
+impl Packet<'_> for ServerPlayerShoot {
+	const ID: u8 = PACKET_ID::S2C_SERVERPLAYERSHOOT;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			bullet_id: View(b, c).try_get_u16()?,
+			shooter_id: View(b, c).try_get_u32()?,
+			unknown: View(b, c).try_get_u32()?,
+			pos_x: View(b, c).try_get_f32()?,
+			pos_y: View(b, c).try_get_f32()?,
+			angle: View(b, c).try_get_f32()?,
+			damage: View(b, c).try_get_u16()?,
+			summoner_id: View(b, c).try_get_u32()?,
+			bullet_type: View(b, c).try_get_u8().ok(),
+			bullet_count: View(b, c).try_get_u8().ok(),
+			angle_between_bullets: View(b, c).try_get_f32().ok(),
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_u16(self.bullet_id);
+		out.put_u32(self.shooter_id);
+		out.put_u32(self.unknown);
+		out.put_f32(self.pos_x);
+		out.put_f32(self.pos_y);
+		out.put_f32(self.angle);
+		out.put_u16(self.damage);
+		out.put_u32(self.summoner_id);
+		if let Some(bullet_type) = self.bullet_type {
+			out.put_u8(bullet_type);
+		}
+		if let Some(bullet_count) = self.bullet_count {
+			out.put_u8(bullet_count);
+		}
+		if let Some(angle) = self.angle_between_bullets {
+			out.put_f32(angle);
+		}
+	}
+}
@@ -0,0 +1,103 @@
+use super::Packet;
+use crate::util::{PACKET_ID, View, read_compressed_int, write_compressed_int};
+use anyhow::{Result, bail};
+use bytes::{Buf, BufMut, BytesMut};
+
+// which optional fields are present
+const COLOR: u8 = 1 << 0;
+const POS1_X: u8 = 1 << 1;
+const POS1_Y: u8 = 1 << 2;
+const POS2_X: u8 = 1 << 3;
+const POS2_Y: u8 = 1 << 4;
+const DURATION: u8 = 1 << 5;
+const OBJECT_ID: u8 = 1 << 6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowEffect {
+	pub effect_type: u8,
+	pub object_id: Option<u32>,
+	pub pos1_x: Option<f32>,
+	pub pos1_y: Option<f32>,
+	pub pos2_x: Option<f32>,
+	pub pos2_y: Option<f32>,
+	pub color: Option<u32>,
+	pub duration: Option<f32>,
+}
+
+impl Packet<'_> for ShowEffect {
+	const ID: u8 = PACKET_ID::S2C_SHOWEFFECT;
+
+	fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		let effect_type = View(b, c).try_get_u8()?;
+		let bitmask = View(b, c).try_get_u8()?;
+		if bitmask & 0x80 != 0 {
+			bail!("unknown ShowEffect bitmask {bitmask:#010b}");
+		}
+
+		Ok(Self {
+			effect_type,
+			object_id: match bitmask & OBJECT_ID {
+				0 => None,
+				_ => Some(read_compressed_int(View(b, c))? as u32),
+			},
+			pos1_x: match bitmask & POS1_X {
+				0 => None,
+				_ => Some(View(b, c).try_get_f32()?),
+			},
+			pos1_y: match bitmask & POS1_Y {
+				0 => None,
+				_ => Some(View(b, c).try_get_f32()?),
+			},
+			pos2_x: match bitmask & POS2_X {
+				0 => None,
+				_ => Some(View(b, c).try_get_f32()?),
+			},
+			pos2_y: match bitmask & POS2_Y {
+				0 => None,
+				_ => Some(View(b, c).try_get_f32()?),
+			},
+			color: match bitmask & COLOR {
+				0 => None,
+				_ => Some(View(b, c).try_get_u32()?),
+			},
+			duration: match bitmask & DURATION {
+				0 => None,
+				_ => Some(View(b, c).try_get_f32()?),
+			},
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		let mut bitmask = 0;
+		for (flag, present) in [
+			(COLOR, self.color.is_some()),
+			(POS1_X, self.pos1_x.is_some()),
+			(POS1_Y, self.pos1_y.is_some()),
+			(POS2_X, self.pos2_x.is_some()),
+			(POS2_Y, self.pos2_y.is_some()),
+			(DURATION, self.duration.is_some()),
+			(OBJECT_ID, self.object_id.is_some()),
+		] {
+			if present {
+				bitmask |= flag;
+			}
+		}
+
+		out.put_u8(self.effect_type);
+		out.put_u8(bitmask);
+		if let Some(id) = self.object_id {
+			write_compressed_int(id as i64, &mut *out);
+		}
+		for pos in [self.pos1_x, self.pos1_y, self.pos2_x, self.pos2_y]
+			.into_iter()
+			.flatten()
+		{
+			out.put_f32(pos);
+		}
+		if let Some(color) = self.color {
+			out.put_u32(color);
+		}
+		if let Some(duration) = self.duration {
+			out.put_f32(duration);
+		}
+	}
+}
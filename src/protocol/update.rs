@@ -0,0 +1,113 @@
+use super::{ObjectStatus, Packet};
+use crate::util::{PACKET_ID, View, read_compressed_int, write_compressed_int};
+use anyhow::{Result, bail};
+use bytes::{Buf, BufMut, BytesMut};
+
+/// Adds/removes objects and tiles that are on the client screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct Update<'a> {
+	pub pos_x: f32,
+	pub pos_y: f32,
+	pub level_type: u8,
+	pub tiles: Vec<Tile>,
+	pub new_objects: Vec<NewObject<'a>>,
+	pub to_remove: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+	pub x: i16,
+	pub y: i16,
+	pub tile_type: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewObject<'a> {
+	pub object_type: u16,
+	pub status: ObjectStatus<'a>,
+}
+
+impl Tile {
+	pub fn decode(b: &BytesMut, c: &mut usize) -> Result<Self> {
+		Ok(Self {
+			x: View(b, c).try_get_i16()?,
+			y: View(b, c).try_get_i16()?,
+			tile_type: View(b, c).try_get_u16()?,
+		})
+	}
+	pub fn encode(&self, out: &mut BytesMut) {
+		out.put_i16(self.x);
+		out.put_i16(self.y);
+		out.put_u16(self.tile_type);
+	}
+}
+
+impl<'a> Packet<'a> for Update<'a> {
+	const ID: u8 = PACKET_ID::S2C_UPDATE;
+
+	fn decode(b: &'a BytesMut, c: &mut usize) -> Result<Self> {
+		let pos_x = View(b, c).try_get_f32()?;
+		let pos_y = View(b, c).try_get_f32()?;
+		let level_type = View(b, c).try_get_u8()?;
+
+		let tiles_n = read_compressed_int(View(b, c))?;
+		if tiles_n < 0 {
+			bail!("negative tiles count {tiles_n}");
+		}
+		let mut tiles = Vec::new();
+		for _ in 0..tiles_n {
+			tiles.push(Tile::decode(b, c)?);
+		}
+
+		let objects_n = read_compressed_int(View(b, c))?;
+		if objects_n < 0 {
+			bail!("negative objects count {objects_n}");
+		}
+		let mut new_objects = Vec::new();
+		for _ in 0..objects_n {
+			new_objects.push(NewObject {
+				object_type: View(b, c).try_get_u16()?,
+				status: ObjectStatus::decode(b, c)?,
+			});
+		}
+
+		let to_remove_n = read_compressed_int(View(b, c))?;
+		if to_remove_n < 0 {
+			bail!("negative to remove count {to_remove_n}");
+		}
+		let mut to_remove = Vec::new();
+		for _ in 0..to_remove_n {
+			to_remove.push(read_compressed_int(View(b, c))? as u32);
+		}
+
+		Ok(Self {
+			pos_x,
+			pos_y,
+			level_type,
+			tiles,
+			new_objects,
+			to_remove,
+		})
+	}
+	fn encode(&self, out: &mut BytesMut) {
+		out.put_f32(self.pos_x);
+		out.put_f32(self.pos_y);
+		out.put_u8(self.level_type);
+
+		write_compressed_int(self.tiles.len() as i64, &mut *out);
+		for tile in &self.tiles {
+			tile.encode(out);
+		}
+
+		write_compressed_int(self.new_objects.len() as i64, &mut *out);
+		for object in &self.new_objects {
+			out.put_u16(object.object_type);
+			object.status.encode(out);
+		}
+
+		write_compressed_int(self.to_remove.len() as i64, &mut *out);
+		for &id in &self.to_remove {
+			write_compressed_int(id as i64, &mut *out);
+		}
+	}
+}
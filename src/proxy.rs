@@ -14,10 +14,13 @@ use tokio::{
 use tracing::instrument;
 
 mod logic;
-mod packets;
 mod reader;
+#[cfg(test)]
+mod replay;
 mod writer;
 
+pub use logic::version::SUPPORTED_BUILD_VERSIONS;
+
 const WRITE_CHANNEL_SIZE: usize = 128;
 
 // RC4 cipher keys (server to client and client to server)
@@ -1,29 +1,39 @@
 use std::sync::Arc;
 
-use super::{Proxy, packets};
+use super::Proxy;
 use crate::{
-	Rotmguard,
+	Direction, Rotmguard,
+	protocol::{C2SPacket, S2CPacket},
 	util::{PACKET_ID, View},
 };
 use antipush::AntiPush;
 use anyhow::Result;
 use autonexus::Autonexus;
-use bytes::{Buf, BytesMut};
+use bytes::BytesMut;
 use common::Common;
 use damage_monitor::DamageMonitor;
+use errors::Errors;
 use fakeslow::FakeSlow;
 use notify::Notify;
+use profiles::Profiles;
 use tracing::{info, warn};
+use version::Version;
 
 pub mod antidebuffs;
 pub mod antilag;
 pub mod antipush;
 pub mod autonexus;
+pub mod commands;
 pub mod common;
 pub mod con;
 pub mod damage_monitor;
+pub mod errors;
 pub mod fakeslow;
+pub mod feature;
+pub mod general;
 pub mod notify;
+pub mod profiles;
+pub mod version;
 
 pub struct State {
 	pub common: Common,
@@ -32,6 +42,9 @@ pub struct State {
 	pub autonexus: Autonexus,
 	pub damage_monitor: DamageMonitor,
 	pub notify: Notify,
+	pub profiles: Profiles,
+	pub version: Version,
+	pub errors: Errors,
 }
 
 impl State {
@@ -39,101 +52,116 @@ impl State {
 		Ok(Self {
 			common: Common::default(),
 			antipush: AntiPush::new(rotmguard)?,
-			fakeslow: Default::default(),
+			fakeslow: FakeSlow::new(rotmguard),
 			autonexus: Default::default(),
 			damage_monitor: DamageMonitor::new(rotmguard),
 			notify: Default::default(),
+			profiles: Default::default(),
+			version: Default::default(),
+			errors: Default::default(),
 		})
 	}
 }
 
-pub async fn handle_c2s_packet(proxy: &mut Proxy, mut packet_bytes: BytesMut) -> Result<()> {
-	let mut packet_parsed = true;
+pub async fn handle_c2s_packet(proxy: &mut Proxy, packet_bytes: BytesMut) -> Result<()> {
+	if proxy.state.version.passthrough {
+		proxy.send_server(packet_bytes.freeze()).await;
+		return Ok(());
+	}
 
-	let cursor = &mut 0;
+	let packet_id = packet_bytes[0];
 
-	let packet_id = View(&packet_bytes, cursor).get_u8();
-	let block_packet = match packet_id {
-		PACKET_ID::C2S_PLAYERTEXT => packets::playertext(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::C2S_MOVE => packets::r#move(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::C2S_PLAYERHIT => packets::playerhit(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::C2S_AOEACK => packets::aoeack(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::C2S_GROUNDDAMAGE => {
-			packets::ground_damage(proxy, &mut packet_bytes, cursor).await?
-		}
-		PACKET_ID::C2S_PLAYERSHOOT => {
-			packets::playershoot(proxy, &mut packet_bytes, cursor).await?
+	let cursor = &mut 1;
+	let mut packet = match C2SPacket::decode(packet_id, &packet_bytes, cursor) {
+		Ok(Some(packet)) => packet,
+		Ok(None) => {
+			proxy.send_server(packet_bytes.freeze()).await;
+			return Ok(());
 		}
-		PACKET_ID::C2S_ENEMYHIT => packets::enemyhit(proxy, &mut packet_bytes, cursor).await?,
-		_ => {
-			packet_parsed = false;
-			false
+		Err(e) => {
+			errors::packet_error(proxy, Direction::C2S, packet_id, e);
+			proxy.send_server(packet_bytes.freeze()).await;
+			return Ok(());
 		}
 	};
+	warn_leftover(packet_id, &packet_bytes, cursor);
 
-	if packet_parsed {
-		let leftover = View(&packet_bytes, cursor).slice();
-		if leftover.len() > 0 {
-			warn!(
-				"Leftover unparsed bytes at [{packet_id}] packet:\n{:?}",
-				&leftover[..leftover.len().min(500)]
-			);
-		}
-	}
+	let verdict = feature::on_c2s(proxy, &mut packet).await;
 
-	if !block_packet {
-		proxy.send_server(packet_bytes.freeze()).await;
+	if verdict.block {
+		return Ok(());
 	}
+	let bytes = if verdict.encode() {
+		packet.to_bytes()
+	} else {
+		packet_bytes.freeze()
+	};
+	proxy.send_server(bytes).await;
 
 	Ok(())
 }
 
-pub async fn handle_s2c_packet(proxy: &mut Proxy, mut packet_bytes: BytesMut) -> Result<()> {
-	let mut packet_parsed = true;
-
-	let cursor = &mut 0;
+pub async fn handle_s2c_packet(proxy: &mut Proxy, packet_bytes: BytesMut) -> Result<()> {
+	if proxy.state.version.passthrough {
+		let packet_id = packet_bytes[0];
+		proxy.send_client(packet_bytes.freeze()).await;
+		version::passthrough_s2c(proxy, packet_id).await;
+		return Ok(());
+	}
 
-	let packet_id = View(&packet_bytes, cursor).get_u8();
-	let block_packet = match packet_id {
-		PACKET_ID::S2C_MAPINFO => packets::mapinfo(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::S2C_UPDATE => packets::update(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::S2C_NEWTICK => packets::newtick(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::S2C_CREATE_SUCCESS => {
-			packets::create_success(proxy, &mut packet_bytes, cursor).await?
-		}
-		PACKET_ID::S2C_ENEMYSHOOT => packets::enemyshoot(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::S2C_NOTIFICATION => {
-			packets::notification(proxy, &mut packet_bytes, cursor).await?
+	let packet_id = packet_bytes[0];
+
+	let cursor = &mut 1;
+	let mut packet = match S2CPacket::decode(packet_id, &packet_bytes, cursor) {
+		Ok(Some(packet)) => packet,
+		Ok(None) => {
+			if packet_id == PACKET_ID::S2C_DEATH {
+				info!("holy shit 💀"); // 🪦 願您在天使的懷抱中找到永恆的和平與安寧。安息。
+				autonexus::death(proxy).await;
+			}
+			proxy.send_client(packet_bytes.freeze()).await;
+			return Ok(());
 		}
-		PACKET_ID::S2C_DAMAGE => packets::damage(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::S2C_AOE => packets::aoe(proxy, &mut packet_bytes, cursor).await?,
-		PACKET_ID::S2C_DEATH => {
-			info!("holy shit 💀"); // 🪦 願您在天使的懷抱中找到永恆的和平與安寧。安息。
-			packet_parsed = false;
-			false
-		}
-		PACKET_ID::S2C_SERVERPLAYERSHOOT => {
-			packets::serverplayershoot(proxy, &mut packet_bytes, cursor).await?
-		}
-		_ => {
-			packet_parsed = false;
-			false
+		Err(e) => {
+			if packet_id == PACKET_ID::S2C_MAPINFO {
+				version::unknown_layout(proxy, e);
+			} else {
+				errors::packet_error(proxy, Direction::S2C, packet_id, e);
+			}
+			proxy.send_client(packet_bytes.freeze()).await;
+			return Ok(());
 		}
 	};
+	warn_leftover(packet_id, &packet_bytes, cursor);
 
-	if packet_parsed {
-		let leftover = View(&packet_bytes, cursor).slice();
-		if leftover.len() > 0 {
-			warn!(
-				"Leftover unparsed bytes at [{packet_id}] packet:\n{:?}",
-				&leftover[..leftover.len().min(500)]
-			);
-		}
+	if let S2CPacket::MapInfo(map_info) = &packet
+		&& !version::check(proxy, map_info.build_version)
+	{
+		proxy.send_client(packet_bytes.freeze()).await;
+		return Ok(());
 	}
 
-	if !block_packet {
-		proxy.send_client(packet_bytes.freeze()).await;
+	let verdict = feature::on_s2c(proxy, &mut packet).await;
+
+	if verdict.block {
+		return Ok(());
 	}
+	let bytes = if verdict.encode() {
+		packet.to_bytes()
+	} else {
+		packet_bytes.freeze()
+	};
+	proxy.send_client(bytes).await;
 
 	Ok(())
 }
+
+fn warn_leftover(packet_id: u8, b: &BytesMut, c: &mut usize) {
+	let leftover = View(b, c).slice();
+	if !leftover.is_empty() {
+		warn!(
+			"Leftover unparsed bytes at [{packet_id}] packet:\n{:?}",
+			&leftover[..leftover.len().min(500)]
+		);
+	}
+}
@@ -1,29 +1,57 @@
-use crate::{proxy::Proxy, util::CONDITION_BITFLAG};
+use super::{
+	errors::FeatureId,
+	feature::{Feature, Verdict, self_condition_stats},
+	profiles,
+};
+use crate::{protocol::S2CPacket, proxy::Proxy, util::CONDITION_BITFLAG};
+use anyhow::Result;
+
+/// Removes the debuffs that are disabled in the config from the player's condition
+pub struct AntiDebuffs;
+
+impl Feature for AntiDebuffs {
+	const ID: FeatureId = FeatureId::AntiDebuffs;
+
+	async fn on_s2c(
+		proxy: &mut Proxy,
+		packet: &mut S2CPacket<'_>,
+		verdict: &mut Verdict,
+	) -> Result<()> {
+		for stat in self_condition_stats(proxy.state.common.objects.self_id, packet) {
+			let original = *stat;
+			self_condition_stat(proxy, stat);
+			verdict.modified |= *stat != original;
+		}
+
+		Ok(())
+	}
+}
 
 /// To be called in NewTick and Update when the condition stat about self is read
 /// may modify the stat
 pub fn self_condition_stat(proxy: &mut Proxy, stat: &mut i64) {
 	let mut bitflags = *stat as u64;
+	let debuffs = profiles::debuffs(proxy);
 
-	if proxy.rotmguard.config.settings.debuffs.blind {
+	if debuffs.blind {
 		bitflags &= !CONDITION_BITFLAG::BLIND;
 	}
-	if proxy.rotmguard.config.settings.debuffs.hallucinating {
+	if debuffs.hallucinating {
 		bitflags &= !CONDITION_BITFLAG::HALLUCINATING;
 	}
-	if proxy.rotmguard.config.settings.debuffs.drunk {
+	if debuffs.drunk {
 		bitflags &= !CONDITION_BITFLAG::DRUNK;
 	}
-	if proxy.rotmguard.config.settings.debuffs.confused {
+	if debuffs.confused {
 		bitflags &= !CONDITION_BITFLAG::CONFUSED;
 	}
-	if proxy.rotmguard.config.settings.debuffs.hexed {
+	if debuffs.hexed {
 		bitflags &= !CONDITION_BITFLAG::HEXED;
 	}
-	if proxy.rotmguard.config.settings.debuffs.unstable {
+	if debuffs.unstable {
 		bitflags &= !CONDITION_BITFLAG::UNSTABLE;
 	}
-	if proxy.rotmguard.config.settings.debuffs.darkness {
+	if debuffs.darkness {
 		bitflags &= !CONDITION_BITFLAG::DARKNESS;
 	}
 
@@ -1,7 +1,74 @@
 //! this basically blocks the packets that have no effect if the client has "ally damage" and "ally notifications"
 //! disabled but still makes the client lag af..
 
-use crate::proxy::Proxy;
+use super::{
+	commands::{Args, Command, Gate},
+	errors::FeatureId,
+	feature::{Feature, Verdict},
+	general, profiles,
+};
+use crate::{
+	protocol::{NotificationKind, S2CPacket},
+	proxy::Proxy,
+	util::{GREEN, RED, static_notification},
+};
+use anyhow::Result;
+
+pub struct AntiLag;
+
+impl Feature for AntiLag {
+	const ID: FeatureId = FeatureId::AntiLag;
+	const COMMANDS: &'static [Command] = &[Command {
+		name: "antilag",
+		aliases: &[],
+		args: &[],
+		description: "Toggles antilag",
+		gate: Gate::Always,
+	}];
+
+	async fn on_s2c(
+		proxy: &mut Proxy,
+		packet: &mut S2CPacket<'_>,
+		verdict: &mut Verdict,
+	) -> Result<()> {
+		match packet {
+			// The packet is used to tell the player about damage done to other players and enemies.
+			S2CPacket::Damage(damage) => {
+				verdict.block |= should_block_damage(proxy, damage.target_id, damage.owner_id);
+			}
+			S2CPacket::Notification(notification) => {
+				if let NotificationKind::ObjectText {
+					message,
+					object_id,
+					color,
+				} = notification.kind
+				{
+					verdict.block |=
+						should_block_object_notification(proxy, object_id, color, message);
+				}
+			}
+			_ => {}
+		}
+
+		Ok(())
+	}
+	async fn on_command(proxy: &mut Proxy, _: &Command, _: &Args<'_>) -> Result<()> {
+		let state = !profiles::antilag(proxy);
+		profiles::set_antilag(proxy, state);
+
+		let notification = if state {
+			static_notification!("antilag on", GREEN)
+		} else {
+			static_notification!("antilag off", RED)
+		};
+
+		proxy.send_client(notification).await;
+
+		general::setting_changed(proxy).await;
+
+		Ok(())
+	}
+}
 
 pub fn should_block_damage(
 	proxy: &mut Proxy,
@@ -12,7 +79,7 @@ pub fn should_block_damage(
 
 	// (we wanna see our own damage!!)
 
-	if *proxy.rotmguard.config.settings.antilag.lock().unwrap() {
+	if profiles::antilag(proxy) {
 		let self_id = proxy.state.common.objects.self_id;
 
 		if bullet_owner_obj_id == self_id || target_obj_id == self_id {
@@ -39,7 +106,7 @@ pub fn should_block_object_notification(
 	};
 
 	if obj_id != self_id && obj.is_player {
-		*proxy.rotmguard.config.settings.antilag.lock().unwrap()
+		profiles::antilag(proxy)
 	} else {
 		false
 	}
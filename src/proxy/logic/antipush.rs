@@ -1,5 +1,12 @@
+use super::{
+	commands::{Args, Command, Gate},
+	errors::FeatureId,
+	feature::{Feature, Verdict},
+	general,
+};
 use crate::{
 	Rotmguard,
+	protocol::{S2CPacket, Tile},
 	proxy::Proxy,
 	util::{GREEN, RED, static_notification},
 };
@@ -33,11 +40,53 @@ impl AntiPush {
 		Ok(Self {
 			replacement_tile: *replacement_tile.0 as u16,
 			conveyor_tiles: Default::default(),
-			enabled: false,
+			enabled: *rotmguard.config.settings.antipush.lock().unwrap(),
 			synced: true,
 		})
 	}
 }
+impl Feature for AntiPush {
+	const ID: FeatureId = FeatureId::AntiPush;
+	const COMMANDS: &'static [Command] = &[Command {
+		name: "ap",
+		aliases: &["antipush"],
+		args: &[],
+		description: "Toggles anti push",
+		gate: Gate::Always,
+	}];
+
+	async fn on_s2c(
+		proxy: &mut Proxy,
+		packet: &mut S2CPacket<'_>,
+		verdict: &mut Verdict,
+	) -> Result<()> {
+		if let S2CPacket::Update(update) = packet {
+			for tile in &mut update.tiles {
+				if let Some(new_tile_id) = new_tile(proxy, tile.x, tile.y, tile.tile_type) {
+					tile.tile_type = new_tile_id;
+					verdict.modified = true;
+				}
+			}
+		}
+
+		Ok(())
+	}
+	async fn on_command(proxy: &mut Proxy, _: &Command, _: &Args<'_>) -> Result<()> {
+		toggle(proxy).await;
+
+		Ok(())
+	}
+	// if antipush not synced, need to add more tile data to replace all previously sent conveyer tiles
+	fn extra_tiles(proxy: &mut Proxy) -> Vec<Tile> {
+		match extra_tile_data(proxy) {
+			Some(tiles) => tiles
+				.map(|(x, y, tile_type)| Tile { x, y, tile_type })
+				.collect(),
+			None => Vec::new(),
+		}
+	}
+}
+
 /// To be called when new tiles enter the player screen or are replaced in the Update packet
 /// Returns a new tile id, if we need to replace the tile type immediatelly in place
 pub fn new_tile(proxy: &mut Proxy, x: i16, y: i16, tile_type: u16) -> Option<u16> {
@@ -73,6 +122,7 @@ pub fn new_tile(proxy: &mut Proxy, x: i16, y: i16, tile_type: u16) -> Option<u16
 pub async fn toggle(proxy: &mut Proxy) {
 	proxy.state.antipush.enabled = !proxy.state.antipush.enabled;
 	proxy.state.antipush.synced = false;
+	*proxy.rotmguard.config.settings.antipush.lock().unwrap() = proxy.state.antipush.enabled;
 
 	let notification = if proxy.state.antipush.enabled {
 		static_notification!("Anti push enabled", GREEN)
@@ -81,6 +131,8 @@ pub async fn toggle(proxy: &mut Proxy) {
 	};
 
 	proxy.send_client(notification).await;
+
+	general::setting_changed(proxy).await;
 }
 
 // checks if antipush is not synced, and if so, returns an iterator of extra tile data
@@ -1,27 +1,43 @@
 use crate::{
+	config::Threshold,
+	protocol::{C2SPacket, NewTick, NotificationKind, ObjectStatus, S2CPacket, StatData},
 	proxy::{
 		Proxy,
-		packets::{ExtraObject, StatData},
+		logic::{
+			commands::{Arg, ArgKind, Args, Command, Gate},
+			errors::FeatureId,
+			feature::{Feature, Verdict},
+			general, profiles,
+		},
 	},
 	util::{
-		BLUE, CONDITION_BITFLAG, CONDITION2_BITFLAG, GREEN, RED, STAT_TYPE, create_escape,
+		BLUE, CONDITION_BITFLAG, CONDITION2_BITFLAG, GREEN, STAT_TYPE, condition_names,
 		create_notification,
 	},
 };
+use anyhow::Result;
 use either::Either;
+use std::collections::VecDeque;
 use tracing::{error, info};
 
 mod aoes;
+mod dry_run;
+mod escape;
 mod ground;
 mod heals;
 mod passive;
 mod projectiles;
+mod recap;
+#[cfg(test)]
+mod tests;
+mod timeline;
 
 pub use aoes::{aoe, aoeack};
 pub use ground::{ground_damage, new_tile};
 pub use heals::object_notification;
 pub use passive::new_tick;
 pub use projectiles::player_hit;
+use timeline::{Cause, Enemy, EventKind, record};
 
 #[derive(Default)]
 pub struct Autonexus {
@@ -31,6 +47,11 @@ pub struct Autonexus {
 	inflicted_conditions: Vec<InflictedCondition>,
 	ground: ground::Ground,
 	aoes: aoes::Aoes,
+	// (time, damage) of the damage taken recently, for predicting
+	recent_damage: VecDeque<(u32, i64)>,
+	timeline: timeline::Timeline,
+	escape: escape::Escape,
+	dry_run: dry_run::DryRun,
 }
 
 struct InflictedCondition {
@@ -40,10 +61,106 @@ struct InflictedCondition {
 	expires_in: u32,
 }
 
-pub async fn command(proxy: &mut Proxy, mut args: impl Iterator<Item = &str>) {
-	let value = match args.next() {
+impl Feature for Autonexus {
+	const ID: FeatureId = FeatureId::Autonexus;
+	const COMMANDS: &'static [Command] = &[
+		Command {
+			name: "autonexus",
+			aliases: &[],
+			args: &[Arg {
+				name: "HP",
+				kinds: &[ArgKind::Threshold],
+				optional: true,
+			}],
+			description: "Shows or sets the autonexus threshold, in HP or % of max HP",
+			gate: Gate::Always,
+		},
+		Command {
+			name: "dryrun",
+			aliases: &[],
+			args: &[],
+			description: "Toggles autonexus dry run, only telling when it would have nexused",
+			gate: Gate::Always,
+		},
+	];
+
+	async fn on_c2s(proxy: &mut Proxy, packet: &mut C2SPacket<'_>, _: &mut Verdict) -> Result<()> {
+		match packet {
+			C2SPacket::Move(r#move) => client_tick_ack(proxy, r#move.tick_id, r#move.time).await,
+			C2SPacket::PlayerHit(hit) => player_hit(proxy, hit.bullet_id, hit.owner_id).await?,
+			C2SPacket::AoeAck(ack) => aoeack(proxy, ack.pos_x, ack.pos_y).await?,
+			C2SPacket::GroundDamage(damage) => {
+				ground_damage(proxy, damage.pos_x as i16, damage.pos_y as i16).await?
+			}
+			_ => {}
+		}
+
+		Ok(())
+	}
+	async fn on_s2c(proxy: &mut Proxy, packet: &mut S2CPacket<'_>, _: &mut Verdict) -> Result<()> {
+		match packet {
+			S2CPacket::Update(update) => {
+				// keep going so that one bad tile doesn't leave holes in the map
+				let mut result = Ok(());
+				for tile in &update.tiles {
+					if let Err(e) = new_tile(proxy, tile.x, tile.y, tile.tile_type) {
+						result = Err(e);
+					}
+				}
+				result?;
+			}
+			// may be flying at us
+			S2CPacket::EnemyShoot(_) if predicting(proxy) => check_health(proxy).await,
+			S2CPacket::MapInfo(map_info) => {
+				dry_run::set_map_name(proxy, map_info.name);
+				escape::left(proxy);
+			}
+			S2CPacket::Reconnect(_) => escape::left(proxy),
+			S2CPacket::Aoe(a) => aoe(proxy, a).await,
+			S2CPacket::Notification(notification) => {
+				if let NotificationKind::ObjectText {
+					message,
+					object_id,
+					color,
+				} = notification.kind
+				{
+					object_notification(proxy, message, object_id, color).await;
+				}
+			}
+			_ => {}
+		}
+
+		Ok(())
+	}
+	async fn on_tick(proxy: &mut Proxy, tick: &NewTick<'_>) -> Result<()> {
+		new_tick(proxy, tick.tick_id, tick.tick_time).await;
+		escape::tick(proxy).await;
+
+		// bullets have moved
+		if predicting(proxy) {
+			check_health(proxy).await;
+		}
+
+		Ok(())
+	}
+	async fn on_command(proxy: &mut Proxy, command: &Command, args: &Args<'_>) -> Result<()> {
+		match command.name {
+			"autonexus" => self::command(proxy, args.threshold(0)).await,
+			"dryrun" => dry_run::command(proxy).await,
+			_ => {}
+		}
+
+		Ok(())
+	}
+	fn extra_object_status(proxy: &mut Proxy) -> Option<ObjectStatus<'static>> {
+		extra_object_status(proxy)
+	}
+}
+
+pub async fn command(proxy: &mut Proxy, threshold: Option<Threshold>) {
+	let parsed = match threshold {
 		None => {
-			let current_value = *proxy.rotmguard.config.settings.autonexus_hp.lock().unwrap();
+			let current_value = profiles::autonexus_hp(proxy);
 			let notification = create_notification(
 				&format!("/autonexus [HP]\nCurrent value: {current_value}",),
 				BLUE,
@@ -51,25 +168,15 @@ pub async fn command(proxy: &mut Proxy, mut args: impl Iterator<Item = &str>) {
 			proxy.send_client(notification).await;
 			return;
 		}
-		Some(v) => v,
+		Some(t) => t,
 	};
 
-	let parsed = match value.parse::<i32>() {
-		Ok(i) => i,
-		Err(e) => {
-			let notification =
-				create_notification(&format!("/autonexus [HP]\nError parsing HP: {e}"), RED);
-			proxy.send_client(notification).await;
-			error!("Error parsing /autonexus command HP: {e:?}");
-			return;
-		}
-	};
+	profiles::set_autonexus_hp(proxy, parsed);
 
-	*proxy.rotmguard.config.settings.autonexus_hp.lock().unwrap() = parsed;
-
-	let notification =
-		create_notification(&format!("Autonexus threshold set to {parsed} HP."), GREEN);
+	let notification = create_notification(&format!("Autonexus threshold set to {parsed}."), GREEN);
 	proxy.send_client(notification).await;
+
+	general::setting_changed(proxy).await;
 }
 
 pub async fn client_tick_ack(proxy: &mut Proxy, tick_id: u32, tick_time: u32) {
@@ -92,23 +199,30 @@ pub async fn client_tick_ack(proxy: &mut Proxy, tick_id: u32, tick_time: u32) {
 	let hp_delta = stats.hp - proxy.state.autonexus.hp.round() as i64;
 	let safe_to_sync = tick_id > proxy.state.autonexus.tick_to_sync_after;
 	if safe_to_sync && hp_delta != 0 {
+		let delta = stats.hp as f32 - proxy.state.autonexus.hp;
 		proxy.state.autonexus.hp = stats.hp as f32;
+		record(proxy, EventKind::Sync { delta });
 	}
 }
 
+// for the post-mortem
+pub async fn death(proxy: &mut Proxy) {
+	record(proxy, EventKind::Death);
+	timeline::dump(proxy, "Died", true).await;
+	recap::publish(proxy).await;
+}
+
 // if devmode enabled will replace the fame bar with simulated hp
-pub fn extra_object_status(
-	proxy: &mut Proxy,
-) -> impl Iterator<Item = ExtraObject<impl Iterator<Item = StatData<'_>> + ExactSizeIterator>> {
+pub fn extra_object_status(proxy: &mut Proxy) -> Option<ObjectStatus<'static>> {
 	if !devmode(proxy) {
-		return None.into_iter();
+		return None;
 	}
 
-	Some(ExtraObject {
-		obj_id: proxy.state.common.objects.self_id,
+	Some(ObjectStatus {
+		object_id: proxy.state.common.objects.self_id,
 		pos_x: 0.0,
 		pos_y: 0.0,
-		stats: [
+		stats: vec![
 			StatData {
 				stat_type: STAT_TYPE::CURRENT_FAME,
 				data: Either::Right(proxy.state.autonexus.hp as i64),
@@ -119,19 +233,36 @@ pub fn extra_object_status(
 				data: Either::Right(proxy.state.common.objects.get_self().stats.max_hp),
 				secondary: -1,
 			},
-		]
-		.into_iter(),
+		],
 	})
-	.into_iter()
 }
 
-// calculates and applies the real damage, taking into account status effects and everything
-async fn take_damage(proxy: &mut Proxy, mut damage: i64, armor_piercing: bool) {
+// calculates and applies the real damage
+async fn take_damage(proxy: &mut Proxy, damage: i64, armor_piercing: bool, cause: Cause) {
+	match calculate_damage(proxy, damage, armor_piercing) {
+		Some(damage) => take_damage_raw(proxy, damage, cause).await,
+		None => {
+			let conditions = get_conditions(proxy);
+			record(
+				proxy,
+				EventKind::Damage {
+					cause,
+					damage: 0,
+					conditions,
+				},
+			);
+		}
+	}
+}
+
+// calculates the real damage, taking into account status effects and everything.
+// None if invulnerable
+fn calculate_damage(proxy: &mut Proxy, mut damage: i64, armor_piercing: bool) -> Option<i64> {
 	let stats = proxy.state.common.objects.get_self().stats;
 	let (conditions, conditions2) = get_conditions(proxy);
 
 	if (conditions & CONDITION_BITFLAG::INVULNERABLE) != 0 {
-		return;
+		return None;
 	}
 
 	// calculate damage
@@ -181,18 +312,37 @@ async fn take_damage(proxy: &mut Proxy, mut damage: i64, armor_piercing: bool) {
 		}
 	}
 
-	take_damage_raw(proxy, damage).await;
+	Some(damage)
 }
 
 // just applies already calculated raw damage
-async fn take_damage_raw(proxy: &mut Proxy, dmg: i64) {
-	let (condition, _condition2) = get_conditions(proxy);
-	if (condition & (CONDITION_BITFLAG::INVINCIBLE | CONDITION_BITFLAG::STASIS)) != 0 {
-		return; // player is invincible/stasis, no damage can be taken
+async fn take_damage_raw(proxy: &mut Proxy, dmg: i64, cause: Cause) {
+	let conditions = get_conditions(proxy);
+	if (conditions.0 & (CONDITION_BITFLAG::INVINCIBLE | CONDITION_BITFLAG::STASIS)) != 0 {
+		// player is invincible/stasis, no damage can be taken
+		record(
+			proxy,
+			EventKind::Damage {
+				cause,
+				damage: 0,
+				conditions,
+			},
+		);
+		return;
 	}
 
 	proxy.state.autonexus.hp -= dmg as f32;
+	let time = proxy.state.common.time;
+	proxy.state.autonexus.recent_damage.push_back((time, dmg));
 	reset_safe_sync_delay(proxy);
+	record(
+		proxy,
+		EventKind::Damage {
+			cause,
+			damage: dmg,
+			conditions,
+		},
+	);
 
 	check_health(proxy).await;
 
@@ -202,18 +352,120 @@ async fn take_damage_raw(proxy: &mut Proxy, dmg: i64) {
 	}
 }
 
+// remembers a condition inflicted by a hit until the server confirms it
+async fn inflict(proxy: &mut Proxy, condition: u64, condition2: u64, duration: f32) {
+	if condition == 0 && condition2 == 0 {
+		return;
+	}
+
+	proxy
+		.state
+		.autonexus
+		.inflicted_conditions
+		.push(InflictedCondition {
+			condition,
+			condition2,
+			expires_in: (duration * 1000.0) as u32,
+		});
+
+	let names = condition_names(condition, condition2).join(", ");
+	record(
+		proxy,
+		EventKind::Inflicted {
+			conditions: names.clone(),
+			duration,
+		},
+	);
+
+	if devmode(proxy) {
+		let notification = create_notification(&format!("{names} {duration}s"), 0x888888);
+		proxy.send_client(notification).await;
+	}
+}
+
 fn reset_safe_sync_delay(proxy: &mut Proxy) {
 	// safe to sync HP only after client acknowledges 10 ticks after current server tick
 	proxy.state.autonexus.tick_to_sync_after = proxy.state.common.server_tick_id + 10;
 }
 
 async fn check_health(proxy: &mut Proxy) {
-	let threshold = *proxy.rotmguard.config.settings.autonexus_hp.lock().unwrap();
-	if proxy.state.autonexus.hp < threshold as f32 {
-		// AUTONEXUS ENGAGE!!!
-		proxy.send_server(create_escape()).await;
+	// already leaving
+	if escape::active(proxy) {
+		return;
+	}
+
+	let max_hp = proxy.state.common.objects.get_self().stats.max_hp;
+	let threshold = profiles::autonexus_hp(proxy).hp(max_hp);
+	let hp = proxy.state.autonexus.hp;
+
+	let below = hp < threshold;
+	let threat = if below { 0 } else { threat(proxy) };
+	let fire = below || (threat > 0 && hp - (threat as f32) < threshold);
+
+	if dry_run::enabled(proxy) {
+		dry_run::check(proxy, hp, fire, threat).await;
+		return;
+	}
+	if !fire {
+		return;
+	}
+
+	// AUTONEXUS ENGAGE!!!
+	if !escape::escape(proxy).await {
+		return;
+	}
+	if threat > 0 {
+		info!("nexusing, expecting {threat} more damage");
+	} else {
 		info!("nexusing");
 	}
+	nexused(proxy, threat).await;
+}
+
+async fn nexused(proxy: &mut Proxy, threat: i64) {
+	record(proxy, EventKind::Nexus { threat });
+	timeline::dump(proxy, "Autonexus", false).await;
+}
+
+fn predicting(proxy: &Proxy) -> bool {
+	*proxy
+		.rotmguard
+		.config
+		.settings
+		.autonexus_predict
+		.lock()
+		.unwrap()
+		> 0.0
+}
+
+// the damage that could be taken soon if predicting is enabled: the recent damage happening
+// again, the queued AoEs landing, and the bullets that are flying at the player landing
+fn threat(proxy: &mut Proxy) -> i64 {
+	let window = *proxy
+		.rotmguard
+		.config
+		.settings
+		.autonexus_predict
+		.lock()
+		.unwrap();
+	let window = (window.max(0.0) * 1000.0) as u32;
+
+	let now = proxy.state.common.time;
+	let recent = &mut proxy.state.autonexus.recent_damage;
+	while recent
+		.front()
+		.is_some_and(|(time, _)| now.wrapping_sub(*time) > window)
+	{
+		recent.pop_front();
+	}
+
+	if window == 0 {
+		return 0;
+	}
+
+	let recent: i64 = recent.iter().map(|(_, damage)| damage).sum();
+
+	recent + aoes::threat(proxy) + projectiles::threat(proxy)
 }
 
 fn devmode(proxy: &mut Proxy) -> bool {
@@ -1,10 +1,8 @@
-use super::{InflictedCondition, take_damage};
-use crate::{
-	proxy::{Proxy, packets::AoeEffect},
-	util::{CONDITION_BITFLAG, CONDITION2_BITFLAG},
-};
+use super::{Cause, Enemy, calculate_damage, check_health, inflict, predicting, take_damage};
+use crate::{protocol::Aoe as AoePacket, proxy::Proxy, util::ConditionEffect};
 use anyhow::{Context, Result};
 use std::collections::VecDeque;
+use tracing::warn;
 
 #[derive(Default)]
 pub struct Aoes {
@@ -19,40 +17,78 @@ struct Aoe {
 	inflicts_condition2: u64,
 	inflicts_duration: f32, // seconds
 	armor_piercing: bool,
+	// object type of whoever threw it
+	orig_type: u16,
 }
 
-pub async fn aoe(
-	proxy: &mut Proxy,
-	pos_x: f32,
-	pos_y: f32,
-	radius: f32,
-	damage: u16,
-	effect: AoeEffect,
-	duration: f32,
-	armor_piercing: bool,
-) {
+impl Aoe {
+	fn hits(&self, pos: (f32, f32)) -> bool {
+		// good ol' pythagorean theorem
+		let distance = ((self.pos.0 - pos.0).powi(2) + (self.pos.1 - pos.1).powi(2)).sqrt();
+
+		distance <= self.radius
+	}
+}
+
+pub async fn aoe(proxy: &mut Proxy, packet: &AoePacket) {
+	let &AoePacket {
+		pos_x,
+		pos_y,
+		radius,
+		damage,
+		effect,
+		duration,
+		orig_type,
+		color: _,
+		armor_piercing,
+	} = packet;
+
+	// 0 is no effect
+	let (inflicts_condition, inflicts_condition2) = match ConditionEffect::by_id(effect) {
+		Some(effect) => effect.bitflags(),
+		None if effect == 0 => (0, 0),
+		None => {
+			warn!("unknown aoe effect {effect}");
+			(0, 0)
+		}
+	};
+
 	let aoe = Aoe {
 		pos: (pos_x, pos_y),
 		radius,
 		damage,
-		inflicts_condition: match effect {
-			AoeEffect::Sick => CONDITION_BITFLAG::SICK,
-			AoeEffect::Bleeding => CONDITION_BITFLAG::BLEEDING,
-			AoeEffect::Weak => CONDITION_BITFLAG::WEAK,
-			AoeEffect::ArmorBroken => CONDITION_BITFLAG::ARMOR_BROKEN,
-			AoeEffect::Stasis => CONDITION_BITFLAG::STASIS,
-			_ => 0,
-		},
-		inflicts_condition2: match effect {
-			AoeEffect::Petrify => CONDITION2_BITFLAG::PETRIFIED,
-			AoeEffect::Curse => CONDITION2_BITFLAG::CURSED,
-			AoeEffect::Exposed => CONDITION2_BITFLAG::EXPOSED,
-			_ => 0,
-		},
+		inflicts_condition,
+		inflicts_condition2,
 		inflicts_duration: duration,
 		armor_piercing,
+		orig_type,
 	};
 	proxy.state.autonexus.aoes.queue.push_back(aoe);
+
+	// in case it's predicted to be deadly
+	if predicting(proxy) {
+		check_health(proxy).await;
+	}
+}
+
+/// The damage of the queued AoEs that would hit the player where it is now
+pub fn threat(proxy: &mut Proxy) -> i64 {
+	let pos = proxy.state.common.my_position;
+	let hits: Vec<(u16, bool)> = proxy
+		.state
+		.autonexus
+		.aoes
+		.queue
+		.iter()
+		.filter(|aoe| aoe.hits(pos))
+		.map(|aoe| (aoe.damage, aoe.armor_piercing))
+		.collect();
+
+	hits.into_iter()
+		.filter_map(|(damage, armor_piercing)| {
+			calculate_damage(proxy, damage as i64, armor_piercing)
+		})
+		.sum()
 }
 
 pub async fn aoeack(proxy: &mut Proxy, pos_x: f32, pos_y: f32) -> Result<()> {
@@ -64,29 +100,25 @@ pub async fn aoeack(proxy: &mut Proxy, pos_x: f32, pos_y: f32) -> Result<()> {
 		.pop_front()
 		.context("client acknowledging aoe when none were sent?")?;
 
-	// good ol' pythagorean theorem
-	let distance = ((aoe.pos.0 - pos_x).powi(2) + (aoe.pos.1 - pos_y).powi(2)).sqrt();
-
-	if distance > aoe.radius {
+	if !aoe.hits((pos_x, pos_y)) {
 		// is fine
 		return Ok(());
 	}
 
 	// hole shit. WE ARE HIT!
-	take_damage(proxy, aoe.damage as i64, aoe.armor_piercing).await;
+	let cause = Cause::Aoe {
+		enemy: Enemy::new(proxy, aoe.orig_type as u32),
+	};
+	take_damage(proxy, aoe.damage as i64, aoe.armor_piercing, cause).await;
 
 	// apply any status effects
-	if aoe.inflicts_condition != 0 {
-		proxy
-			.state
-			.autonexus
-			.inflicted_conditions
-			.push(InflictedCondition {
-				condition: aoe.inflicts_condition,
-				condition2: aoe.inflicts_condition2,
-				expires_in: (aoe.inflicts_duration * 1000.0) as u32,
-			});
-	}
+	inflict(
+		proxy,
+		aoe.inflicts_condition,
+		aoe.inflicts_condition2,
+		aoe.inflicts_duration,
+	)
+	.await;
 
 	Ok(())
 }
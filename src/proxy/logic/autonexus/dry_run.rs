@@ -0,0 +1,147 @@
+//! Autonexus that only tells when it would have nexused, for tuning the threshold without
+//! nexusing for real.
+
+use super::{EventKind, record, timeline};
+use crate::{
+	proxy::{
+		Proxy,
+		logic::{general, notify},
+	},
+	util::{BLUE, GREEN, RED, create_notification},
+};
+use tracing::{error, info};
+
+#[derive(Default)]
+pub struct DryRun {
+	// how many times autonexus would have fired in this dungeon
+	would_have: u32,
+	// it would still be firing since the last time, not counted again until it stops
+	firing: bool,
+	// lowest simulated HP seen in this dungeon
+	lowest_hp: Option<f32>,
+	map_name: Option<String>,
+}
+
+impl DryRun {
+	// returns true if autonexus would fire now and wasn't firing before
+	fn update(&mut self, hp: f32, fire: bool) -> bool {
+		self.lowest_hp = Some(self.lowest_hp.map_or(hp, |lowest| lowest.min(hp)));
+
+		let new = fire && !self.firing;
+		self.firing = fire;
+		if new {
+			self.would_have += 1;
+		}
+
+		new
+	}
+	fn summary(&self) -> String {
+		let lowest = match self.lowest_hp {
+			Some(hp) => format!("{}", hp.round()),
+			None => "-".to_owned(),
+		};
+
+		format!(
+			"Would have nexused {} times, lowest HP {lowest}",
+			self.would_have
+		)
+	}
+}
+
+pub fn enabled(proxy: &Proxy) -> bool {
+	*proxy
+		.rotmguard
+		.config
+		.settings
+		.autonexus_dry_run
+		.lock()
+		.unwrap()
+}
+
+pub fn set_map_name(proxy: &mut Proxy, name: &str) {
+	proxy.state.autonexus.dry_run.map_name = Some(name.to_owned());
+}
+
+/// Instead of escaping
+pub async fn check(proxy: &mut Proxy, hp: f32, fire: bool, threat: i64) {
+	if !proxy.state.autonexus.dry_run.update(hp, fire) {
+		return;
+	}
+
+	let message = if threat > 0 {
+		format!(
+			"Would have nexused at {} HP, expecting {threat} more damage",
+			hp.round()
+		)
+	} else {
+		format!("Would have nexused at {} HP", hp.round())
+	};
+	info!("{message}");
+	proxy.send_client(create_notification(&message, RED)).await;
+	tokio::task::spawn_blocking(move || {
+		if let Err(e) = notify::desktop_notification(&message) {
+			error!("error displaying desktop notification: {e:?}");
+		}
+	});
+
+	record(proxy, EventKind::Nexus { threat });
+	timeline::dump(proxy, "Autonexus (dry run)", false).await;
+}
+
+/// `/dryrun`, toggles the dry run and shows the stats so far
+pub async fn command(proxy: &mut Proxy) {
+	let state = {
+		let mut dry_run = proxy
+			.rotmguard
+			.config
+			.settings
+			.autonexus_dry_run
+			.lock()
+			.unwrap();
+		*dry_run = !*dry_run;
+		*dry_run
+	};
+
+	let summary = proxy.state.autonexus.dry_run.summary();
+	let notification = if state {
+		create_notification(&format!("Autonexus dry run on\n{summary}"), GREEN)
+	} else {
+		create_notification(&format!("Autonexus dry run off\n{summary}"), BLUE)
+	};
+	proxy.send_client(notification).await;
+
+	general::setting_changed(proxy).await;
+}
+
+// the stats of the dungeon
+impl Drop for DryRun {
+	fn drop(&mut self) {
+		if self.lowest_hp.is_none() {
+			return; // wasn't on
+		}
+
+		let map_name = self.map_name.as_deref().unwrap_or("unknown map");
+		info!("Autonexus dry run in {map_name}: {}", self.summary());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn counting() {
+		let mut dry_run = DryRun::default();
+
+		assert!(!dry_run.update(100.0, false));
+		assert!(dry_run.update(10.0, true));
+		// still the same time
+		assert!(!dry_run.update(5.0, true));
+		assert!(!dry_run.update(50.0, false));
+		assert!(dry_run.update(15.0, true));
+
+		assert_eq!(dry_run.would_have, 2);
+		assert_eq!(dry_run.lowest_hp, Some(5.0));
+		assert_eq!(dry_run.summary(), "Would have nexused 2 times, lowest HP 5");
+	}
+}
@@ -0,0 +1,101 @@
+//! Making sure the escape actually happens. Escaping can be disallowed in some places or the
+//! server can be lagging, so if the server doesn't send us away soon enough it's tried again,
+//! and then optionally the client is reconnected to another server.
+
+use super::{EventKind, record};
+use crate::{
+	proxy::{Proxy, logic::con},
+	util::create_escape,
+};
+use tracing::{info, warn};
+
+// how long to wait for the server to send us away, in milliseconds of game time
+const DEADLINE_MS: u32 = 1000;
+// how many times to resend the escape before falling back
+const RESENDS: u32 = 2;
+
+#[derive(Default)]
+pub struct Escape {
+	pending: Option<Pending>,
+	// only try the fallback once, until we're somewhere else
+	fell_back: bool,
+}
+
+struct Pending {
+	// game time of the last attempt
+	sent_at: u32,
+	resent: u32,
+}
+
+/// Whether an escape is on the way, or it didn't work and the client was reconnected instead
+pub fn active(proxy: &Proxy) -> bool {
+	let escape = &proxy.state.autonexus.escape;
+	escape.pending.is_some() || escape.fell_back
+}
+
+/// Sends an escape, unless one is already on the way. Returns whether it was sent
+pub async fn escape(proxy: &mut Proxy) -> bool {
+	if active(proxy) {
+		return false;
+	}
+
+	proxy.send_server(create_escape()).await;
+	proxy.state.autonexus.escape.pending = Some(Pending {
+		sent_at: proxy.state.common.time,
+		resent: 0,
+	});
+
+	true
+}
+
+/// The server is sending us somewhere, the escape (or the fallback) worked
+pub fn left(proxy: &mut Proxy) {
+	proxy.state.autonexus.escape = Escape::default();
+}
+
+/// To be called on every tick, retries if the deadline has passed
+pub async fn tick(proxy: &mut Proxy) {
+	let now = proxy.state.common.time;
+	let Some(pending) = &mut proxy.state.autonexus.escape.pending else {
+		return;
+	};
+	if now.wrapping_sub(pending.sent_at) < DEADLINE_MS {
+		return;
+	}
+
+	if pending.resent < RESENDS {
+		pending.resent += 1;
+		pending.sent_at = now;
+		let attempt = pending.resent;
+
+		warn!("Escape didn't work, trying again");
+		proxy.send_server(create_escape()).await;
+		record(proxy, EventKind::EscapeRetry { attempt });
+		return;
+	}
+
+	proxy.state.autonexus.escape.pending = None;
+
+	let fallback = proxy
+		.rotmguard
+		.config
+		.settings
+		.autonexus_fallback
+		.lock()
+		.unwrap()
+		.clone();
+	match fallback {
+		Some(server) => {
+			// stays inactive if the server is unknown, so the next hit can try escaping again
+			if !con::con(proxy, &server).await {
+				warn!("Escape didn't work, and can't reconnect to unknown server {server}");
+				return;
+			}
+
+			info!("Escape didn't work, reconnected to {server}");
+			proxy.state.autonexus.escape.fell_back = true;
+			record(proxy, EventKind::Fallback { server });
+		}
+		None => warn!("Escape didn't work, giving up"),
+	}
+}
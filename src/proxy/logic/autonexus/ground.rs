@@ -1,4 +1,4 @@
-use super::take_damage_raw;
+use super::{Cause, take_damage_raw};
 use crate::proxy::Proxy;
 use anyhow::Context;
 use anyhow::Result;
@@ -45,7 +45,7 @@ pub async fn ground_damage(proxy: &mut Proxy, x: i16, y: i16) -> Result<()> {
 		}
 	};
 
-	take_damage_raw(proxy, damage as i64).await;
+	take_damage_raw(proxy, damage as i64, Cause::Ground).await;
 
 	Ok(())
 }
@@ -1,3 +1,4 @@
+use super::{EventKind, record};
 use crate::{
 	proxy::{Proxy, logic::autonexus::devmode},
 	util::create_notification,
@@ -50,6 +51,12 @@ pub async fn object_notification(proxy: &mut Proxy, message: &str, object_id: u3
 		.autonexus
 		.hp
 		.min(proxy.state.common.objects.get_self().stats.max_hp as f32);
+	record(
+		proxy,
+		EventKind::Heal {
+			amount: amount_healed,
+		},
+	);
 	if devmode(proxy) {
 		proxy
 			.send_client(create_notification(
@@ -1,4 +1,4 @@
-use super::{check_health, devmode, get_conditions, reset_safe_sync_delay};
+use super::{EventKind, check_health, devmode, get_conditions, record, reset_safe_sync_delay};
 use crate::{
 	proxy::Proxy,
 	util::{CONDITION_BITFLAG, create_effect, create_notification},
@@ -15,8 +15,16 @@ pub async fn new_tick(proxy: &mut Proxy, _id: u32, time: u32) {
 		let bleed_amount = 20.0 * time_seconds;
 
 		// bleeding stops at 1
-		proxy.state.autonexus.hp = (proxy.state.autonexus.hp - bleed_amount).max(1.0);
+		let before = proxy.state.autonexus.hp;
+		proxy.state.autonexus.hp = (before - bleed_amount).max(1.0);
 		reset_safe_sync_delay(proxy);
+		record(
+			proxy,
+			EventKind::DamageOverTime {
+				cause: "Bleeding",
+				amount: before - proxy.state.autonexus.hp,
+			},
+		);
 	} else if (conditions & CONDITION_BITFLAG::SICK) == 0 {
 		// if not sick
 
@@ -68,14 +76,29 @@ pub async fn new_tick(proxy: &mut Proxy, _id: u32, time: u32) {
 				regen_amount += 20.0 * time_seconds;
 			}
 
-			proxy.state.autonexus.hp =
-				(proxy.state.autonexus.hp + regen_amount).min(stats.max_hp as f32);
+			let before = proxy.state.autonexus.hp;
+			proxy.state.autonexus.hp = (before + regen_amount).min(stats.max_hp as f32);
+			if proxy.state.autonexus.hp > before {
+				record(
+					proxy,
+					EventKind::Regen {
+						amount: proxy.state.autonexus.hp - before,
+					},
+				);
+			}
 		}
 	}
 
 	if let Some(breath) = stats.breath {
 		if breath == 0 {
 			proxy.state.autonexus.hp -= time_seconds * 100.0;
+			record(
+				proxy,
+				EventKind::DamageOverTime {
+					cause: "Out of breath",
+					amount: time_seconds * 100.0,
+				},
+			);
 
 			check_health(proxy).await;
 			reset_safe_sync_delay(proxy);
@@ -85,6 +108,13 @@ pub async fn new_tick(proxy: &mut Proxy, _id: u32, time: u32) {
 	if let Some(blizzard) = stats.blizzard {
 		if blizzard >= 100 {
 			proxy.state.autonexus.hp -= time_seconds * 100.0;
+			record(
+				proxy,
+				EventKind::DamageOverTime {
+					cause: "Frozen by blizzard",
+					amount: time_seconds * 100.0,
+				},
+			);
 
 			check_health(proxy).await;
 			reset_safe_sync_delay(proxy);
@@ -121,5 +151,11 @@ pub async fn new_tick(proxy: &mut Proxy, _id: u32, time: u32) {
 		}
 
 		proxy.state.autonexus.hp = stats.hp as f32;
+		record(
+			proxy,
+			EventKind::Sync {
+				delta: hp_delta as f32,
+			},
+		);
 	}
 }
@@ -1,36 +1,134 @@
-use super::{InflictedCondition, take_damage};
-use crate::proxy::{Proxy, logic::common::bullets::BulletId};
+use super::{Cause, Enemy, calculate_damage, inflict, take_damage};
+use crate::{
+	assets::ProjectileInfo,
+	proxy::{
+		Proxy,
+		logic::common::bullets::{BulletId, Shot, trajectory},
+	},
+};
 use anyhow::{Result, bail};
+use std::f32::consts::SQRT_2;
+
+// how far ahead to look for bullets that are going to hit
+const LOOKAHEAD_MS: u32 = 600;
+// how often to check where the bullet is
+const STEP_MS: usize = 20;
+// half of the side of the player's square hitbox
+const HITBOX: f32 = 0.5;
 
 pub async fn player_hit(proxy: &mut Proxy, bullet_id: u16, owner_id: u32) -> Result<()> {
-	let bullet = match proxy.state.common.bullets.cache.get(&BulletId {
+	let id = BulletId {
 		id: bullet_id,
 		owner_id,
-	}) {
+	};
+	let bullet = match proxy.state.common.bullets.cache.get(&id) {
 		Some(x) => *x,
 		None => bail!("Player claims that he got hit by bullet which is not visible."),
 	};
 
+	// already landed, not a threat anymore
+	proxy
+		.state
+		.common
+		.bullets
+		.flying
+		.retain(|flying| *flying != id);
+
+	let cause = Cause::Bullet {
+		id,
+		bullet_type: bullet.bullet_type,
+		enemy: Enemy::new(proxy, bullet.object_type),
+	};
+
 	take_damage(
 		proxy,
 		bullet.damage as i64,
 		bullet.get_properties(proxy)?.armor_piercing,
+		cause,
 	)
 	.await;
 
 	// immediatelly apply any status effects (conditions) if this bullet inflicts
-	proxy.state.autonexus.inflicted_conditions.extend(
-		bullet
-			.get_properties(proxy)?
-			.inflicts
-			.clone()
-			.iter()
-			.map(|c| InflictedCondition {
-				condition: c.condition,
-				condition2: c.condition2,
-				expires_in: (c.duration * 1000.0) as u32,
-			}),
-	);
+	for c in bullet.get_properties(proxy)?.inflicts.clone() {
+		inflict(proxy, c.condition, c.condition2, c.duration).await;
+	}
 
 	Ok(())
 }
+
+/// The damage of the enemy bullets that are going to hit the player in the next few ticks,
+/// if it stays where it is now
+pub fn threat(proxy: &mut Proxy) -> i64 {
+	let now = proxy.state.common.time;
+	let pos = proxy.state.common.my_position;
+
+	let bullets = &proxy.state.common.bullets;
+	let mut hits = Vec::new();
+	for id in &bullets.flying {
+		let Some(bullet) = bullets.cache.peek(id) else {
+			continue;
+		};
+		let (Some(shot), Ok(info)) = (bullet.shot, bullet.get_properties(proxy)) else {
+			continue;
+		};
+
+		let elapsed = now.wrapping_sub(shot.time);
+		if !can_reach(info, id.id, &shot, elapsed, pos) {
+			continue;
+		}
+
+		if will_hit(info, id.id, &shot, elapsed, pos) {
+			hits.push((bullet.damage, info.armor_piercing));
+		}
+	}
+
+	hits.into_iter()
+		.filter_map(|(damage, armor_piercing)| {
+			calculate_damage(proxy, damage as i64, armor_piercing)
+		})
+		.sum()
+}
+
+// whether the bullet can get close enough to the player at all in the time being looked at,
+// so that the ones that can't don't have to be followed step by step
+fn can_reach(
+	info: &ProjectileInfo,
+	bullet_id: u16,
+	shot: &Shot,
+	elapsed: u32,
+	pos: (f32, f32),
+) -> bool {
+	let until = lookahead(info, elapsed);
+	if elapsed > until {
+		return false;
+	}
+
+	let (x, y) = trajectory::position(info, bullet_id, shot, elapsed as f32);
+	let reach = trajectory::reach(info, elapsed as f32, until as f32);
+
+	// the corners of the hitbox are the furthest it can be hit from
+	(x - pos.0).hypot(y - pos.1) <= reach + HITBOX * SQRT_2
+}
+
+// until when to follow the bullet
+fn lookahead(info: &ProjectileInfo, elapsed: u32) -> u32 {
+	elapsed
+		.saturating_add(LOOKAHEAD_MS)
+		.min(info.movement.lifetime_ms)
+}
+
+fn will_hit(
+	info: &ProjectileInfo,
+	bullet_id: u16,
+	shot: &Shot,
+	elapsed: u32,
+	pos: (f32, f32),
+) -> bool {
+	let until = lookahead(info, elapsed);
+
+	(elapsed..=until).step_by(STEP_MS).any(|t| {
+		let (x, y) = trajectory::position(info, bullet_id, shot, t as f32);
+
+		(x - pos.0).abs() <= HITBOX && (y - pos.1).abs() <= HITBOX
+	})
+}
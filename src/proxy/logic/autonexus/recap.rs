@@ -0,0 +1,171 @@
+//! What killed the player, from the damage in the timeline.
+
+use super::timeline::{Cause, EventKind, Timeline};
+use crate::{
+	assets::Assets,
+	damage_monitor_http_server::{DeathRecap, RecapHit, RecapSource},
+	proxy::Proxy,
+	util::{RED, condition_names, create_notification},
+};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Publishes the death recap on the http server
+pub async fn publish(proxy: &mut Proxy) {
+	let max_hp = proxy.state.common.objects.get_self().stats.max_hp;
+	let page = death_recap(
+		&proxy.state.autonexus.timeline,
+		proxy.state.common.time,
+		max_hp,
+		&proxy.rotmguard.assets,
+	);
+
+	if let Some(id) = proxy.rotmguard.damage_monitor_http.add_death_recap(page) {
+		let port = proxy.rotmguard.damage_monitor_http.port();
+		let url = format!("http://127.0.0.1:{port}/death/{id}");
+		info!("Death recap at {url}");
+		proxy
+			.send_client(create_notification(&format!("Death recap at\n{url}"), RED))
+			.await;
+	}
+}
+
+fn death_recap(timeline: &Timeline, now: u32, max_hp: i64, assets: &Assets) -> DeathRecap {
+	let mut hits = Vec::new();
+	// (name, object type) -> (hits, damage)
+	let mut sources: BTreeMap<(String, Option<u32>), (usize, i64)> = BTreeMap::new();
+
+	for event in &timeline.events {
+		let EventKind::Damage {
+			cause,
+			damage,
+			conditions,
+		} = &event.kind
+		else {
+			continue;
+		};
+
+		let (source, object_type) = match cause {
+			Cause::Bullet { enemy, .. } => (enemy.name.clone(), Some(enemy.object_type)),
+			Cause::Aoe { enemy } => (format!("{} (AoE)", enemy.name), Some(enemy.object_type)),
+			Cause::Ground => ("Ground".to_owned(), None),
+		};
+
+		let total = sources.entry((source.clone(), object_type)).or_default();
+		total.0 += 1;
+		total.1 += damage;
+
+		hits.push(RecapHit {
+			time: format!("-{:.2}s", now.wrapping_sub(event.time) as f32 / 1000.0),
+			source,
+			object_type,
+			damage: *damage,
+			conditions: condition_names(conditions.0, conditions.1).join(", "),
+			hp: event.hp.round() as i64,
+			server_hp: event.server_hp,
+		});
+	}
+
+	let mut sources: Vec<RecapSource> = sources
+		.into_iter()
+		.map(|((name, object_type), (hits, damage))| RecapSource {
+			name,
+			object_type,
+			hits,
+			damage,
+		})
+		.collect();
+	sources.sort_by_key(|s| -s.damage); // negative to make it descending
+
+	let all_enemies = sources
+		.iter()
+		.filter_map(|source| source.object_type)
+		.map(|object_type| {
+			let sprite = assets
+				.try_get_obj_sprite(object_type)
+				.map(|raw_sprite| BASE64_STANDARD.encode(raw_sprite));
+			(object_type, sprite)
+		})
+		.collect();
+
+	DeathRecap {
+		time: format!("{}", chrono::Local::now().format("%F %T")),
+		max_hp,
+		sources,
+		hits,
+		all_enemies,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		proxy::{
+			logic::autonexus::{
+				record,
+				timeline::{Cause, Enemy},
+			},
+			replay::{Replay, test_rotmguard},
+		},
+		util::CONDITION_BITFLAG,
+	};
+
+	#[tokio::test]
+	async fn sources() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+		let proxy = replay.proxy();
+
+		let enemy = |name: &str| Enemy {
+			object_type: 5,
+			name: name.to_owned(),
+		};
+		let hits = [
+			(Cause::Ground, 10, 0),
+			(
+				Cause::Aoe {
+					enemy: enemy("Pirate"),
+				},
+				30,
+				0,
+			),
+			(
+				Cause::Aoe {
+					enemy: enemy("Pirate"),
+				},
+				40,
+				CONDITION_BITFLAG::ARMOR_BROKEN,
+			),
+		];
+		for (cause, damage, condition) in hits {
+			record(
+				proxy,
+				EventKind::Damage {
+					cause,
+					damage,
+					conditions: (condition, 0),
+				},
+			);
+		}
+		record(proxy, EventKind::Heal { amount: 100 });
+
+		let recap = death_recap(
+			&proxy.state.autonexus.timeline,
+			0,
+			100,
+			&proxy.rotmguard.assets,
+		);
+
+		let sources: Vec<_> = recap
+			.sources
+			.iter()
+			.map(|s| (s.name.as_str(), s.hits, s.damage))
+			.collect();
+		assert_eq!(sources, [("Pirate (AoE)", 2, 70), ("Ground", 1, 10)]);
+
+		assert_eq!(recap.hits.len(), 3);
+		assert_eq!(recap.hits[2].conditions, "Armor Broken");
+		assert_eq!(recap.all_enemies.keys().collect::<Vec<_>>(), [&5]);
+	}
+}
@@ -0,0 +1,332 @@
+//! Scripted sequences of hits, ticks, heals and acknowledgements, checking the simulated HP.
+
+use super::*;
+use crate::{
+	Rotmguard,
+	assets::{Enchantment, EnchantmentEffect},
+	proxy::{
+		logic::common::objects::{Item, Stats},
+		replay::{Replay, test_rotmguard},
+	},
+};
+
+const SELF_ID: u32 = 1;
+const ENCHANTMENT: u32 = 7;
+
+// a player that nothing else happens to
+struct Sim {
+	replay: Replay,
+	tick_id: u32,
+}
+
+impl Sim {
+	fn new(max_hp: i64, def: i64, vit: i64) -> Self {
+		Self::with(test_rotmguard(), max_hp, def, vit)
+	}
+	fn with(rotmguard: Rotmguard, max_hp: i64, def: i64, vit: i64) -> Self {
+		let mut replay = Replay::new(rotmguard).unwrap();
+
+		let proxy = replay.proxy();
+		proxy.state.common.objects.self_id = SELF_ID;
+		proxy.state.common.objects.get_self().stats = Stats {
+			hp: max_hp,
+			max_hp,
+			def,
+			vit,
+			..Default::default()
+		};
+		proxy.state.autonexus.hp = max_hp as f32;
+
+		Self { replay, tick_id: 0 }
+	}
+	fn proxy(&mut self) -> &mut Proxy {
+		self.replay.proxy()
+	}
+	fn stats(&mut self) -> &mut Stats {
+		&mut self.proxy().state.common.objects.get_self().stats
+	}
+	fn hp(&mut self) -> f32 {
+		self.proxy().state.autonexus.hp
+	}
+	fn set_hp(&mut self, hp: f32) {
+		self.proxy().state.autonexus.hp = hp;
+	}
+	#[track_caller]
+	fn assert_hp(&mut self, expected: f32) {
+		let hp = self.hp();
+		assert!(
+			(hp - expected).abs() < 0.01,
+			"simulated {hp} HP, expected {expected}"
+		);
+	}
+	async fn hit(&mut self, damage: i64) {
+		take_damage(self.proxy(), damage, false, Cause::Ground).await;
+	}
+	async fn hit_armor_piercing(&mut self, damage: i64) {
+		take_damage(self.proxy(), damage, true, Cause::Ground).await;
+	}
+	async fn inflict(&mut self, condition: u64, condition2: u64, seconds: f32) {
+		inflict(self.proxy(), condition, condition2, seconds).await;
+	}
+	// a server tick of the given length
+	async fn tick(&mut self, ms: u32) {
+		self.tick_id += 1;
+		let tick_id = self.tick_id;
+		self.proxy().state.common.server_tick_id = tick_id;
+		new_tick(self.proxy(), tick_id, ms).await;
+	}
+	// the client acknowledging the given tick, the given time after the last one
+	async fn ack(&mut self, tick_id: u32, ms: u32) {
+		client_tick_ack(self.proxy(), tick_id, ms).await;
+	}
+	async fn heal(&mut self, amount: i64) {
+		let message = format!(r#"{{"k":"s.plus_symbol","t":{{"amount":"{amount}"}}}}"#);
+		object_notification(self.proxy(), &message, SELF_ID, 0x00ff00).await;
+	}
+}
+
+#[tokio::test]
+async fn armor() {
+	let mut sim = Sim::new(1000, 30, 0);
+
+	sim.hit(100).await;
+	sim.assert_hp(930.0);
+
+	// at least 10% always goes through
+	sim.stats().def = 200;
+	sim.hit(100).await;
+	sim.assert_hp(920.0);
+
+	sim.hit_armor_piercing(100).await;
+	sim.assert_hp(820.0);
+
+	sim.stats().conditions = CONDITION_BITFLAG::ARMOR_BROKEN;
+	sim.hit(100).await;
+	sim.assert_hp(720.0);
+
+	// 1.5x defense
+	sim.stats().def = 30;
+	sim.stats().conditions = CONDITION_BITFLAG::ARMORED;
+	sim.hit(100).await;
+	sim.assert_hp(665.0);
+}
+
+#[tokio::test]
+async fn damage_conditions() {
+	let mut sim = Sim::new(1000, 0, 0);
+
+	sim.stats().conditions2 = CONDITION2_BITFLAG::EXPOSED;
+	sim.hit(100).await;
+	sim.assert_hp(880.0);
+
+	sim.stats().conditions2 = CONDITION2_BITFLAG::CURSED;
+	sim.hit(100).await;
+	sim.assert_hp(755.0);
+
+	sim.stats().conditions2 = CONDITION2_BITFLAG::PETRIFIED;
+	sim.hit(100).await;
+	sim.assert_hp(665.0);
+
+	// exposed first, then cursed, then petrified: (100 + 20) * 1.25 * 0.9
+	sim.stats().conditions2 =
+		CONDITION2_BITFLAG::EXPOSED | CONDITION2_BITFLAG::CURSED | CONDITION2_BITFLAG::PETRIFIED;
+	sim.hit(100).await;
+	sim.assert_hp(530.0);
+
+	sim.stats().conditions2 = 0;
+	for condition in [
+		CONDITION_BITFLAG::INVULNERABLE,
+		CONDITION_BITFLAG::INVINCIBLE,
+		CONDITION_BITFLAG::STASIS,
+	] {
+		sim.stats().conditions = condition;
+		sim.hit(100).await;
+		sim.hit_armor_piercing(100).await;
+	}
+	sim.assert_hp(530.0);
+}
+
+#[tokio::test]
+async fn enchantments() {
+	let mut rotmguard = test_rotmguard();
+	rotmguard.assets.enchantments.insert(
+		ENCHANTMENT,
+		Enchantment {
+			name: "Resistance".to_owned(),
+			effects: vec![EnchantmentEffect::SelfDamageMult(0.8)],
+		},
+	);
+	let mut sim = Sim::with(rotmguard, 1000, 0, 0);
+	sim.proxy().state.common.objects.get_self().equipped_items[1] = Some(Item {
+		id: 1,
+		enchantments: [ENCHANTMENT].into_iter().collect(),
+	});
+
+	sim.hit(100).await;
+	sim.assert_hp(920.0);
+
+	// rounded up
+	sim.hit(11).await;
+	sim.assert_hp(911.0);
+}
+
+#[tokio::test]
+async fn inflicted_conditions() {
+	let mut sim = Sim::new(1000, 50, 0);
+
+	// before the server tells us, the bullet already broke the armor
+	sim.inflict(CONDITION_BITFLAG::ARMOR_BROKEN, 0, 1.0).await;
+	sim.hit(100).await;
+	sim.assert_hp(900.0);
+
+	sim.inflict(0, CONDITION2_BITFLAG::EXPOSED, 0.5).await;
+	sim.hit(100).await;
+	sim.assert_hp(780.0);
+
+	// exposed wears off
+	sim.ack(1, 600).await;
+	sim.hit(100).await;
+	sim.assert_hp(680.0);
+
+	// and armor broken too
+	sim.ack(2, 600).await;
+	sim.hit(100).await;
+	sim.assert_hp(630.0);
+}
+
+#[tokio::test]
+async fn regeneration() {
+	let mut sim = Sim::new(1000, 0, 0);
+	// the server always says a bit more, so that it doesn't sync
+	sim.stats().hp = 999;
+
+	sim.set_hp(500.0);
+	sim.tick(1000).await;
+	sim.assert_hp(502.0);
+
+	sim.stats().vit = 50;
+	sim.tick(500).await;
+	sim.assert_hp(502.0 + 0.5 * (2.0 + 0.2407 * 50.0));
+
+	// halved in combat
+	sim.set_hp(500.0);
+	sim.stats().conditions = CONDITION_BITFLAG::IN_COMBAT;
+	sim.tick(1000).await;
+	sim.assert_hp(500.0 + (2.0 + 0.2407 * 50.0) / 2.0);
+
+	// healing adds 20 per second
+	sim.set_hp(500.0);
+	sim.stats().vit = 0;
+	sim.stats().conditions = CONDITION_BITFLAG::HEALING;
+	sim.tick(1000).await;
+	sim.assert_hp(522.0);
+
+	// no regeneration while sick
+	sim.set_hp(500.0);
+	sim.stats().conditions = CONDITION_BITFLAG::SICK;
+	sim.tick(1000).await;
+	sim.assert_hp(500.0);
+
+	// not at all if the server says it's full
+	sim.set_hp(500.0);
+	sim.stats().conditions = 0;
+	sim.stats().hp = 1000;
+	sim.tick(1000).await;
+	sim.assert_hp(500.0);
+}
+
+#[tokio::test]
+async fn damage_over_time() {
+	let mut sim = Sim::new(1000, 0, 0);
+	sim.stats().hp = 999;
+	sim.set_hp(500.0);
+
+	// 20 per second
+	sim.stats().conditions = CONDITION_BITFLAG::BLEEDING;
+	sim.tick(500).await;
+	sim.assert_hp(490.0);
+
+	// but stops at 1
+	sim.set_hp(5.0);
+	sim.tick(1000).await;
+	sim.assert_hp(1.0);
+
+	// 100 per second when out of breath
+	sim.set_hp(500.0);
+	sim.stats().conditions = 0;
+	sim.stats().hp = 1000;
+	sim.stats().breath = Some(0);
+	sim.tick(200).await;
+	sim.assert_hp(480.0);
+
+	sim.stats().breath = Some(50);
+	sim.stats().blizzard = Some(100);
+	sim.tick(200).await;
+	sim.assert_hp(460.0);
+}
+
+#[tokio::test]
+async fn heals() {
+	let mut sim = Sim::new(1000, 0, 0);
+	sim.set_hp(500.0);
+
+	sim.heal(120).await;
+	sim.assert_hp(620.0);
+
+	sim.heal(1000).await;
+	sim.assert_hp(1000.0);
+
+	// not green or not us
+	sim.set_hp(500.0);
+	let message = r#"{"k":"s.plus_symbol","t":{"amount":"100"}}"#;
+	object_notification(sim.proxy(), message, SELF_ID, 0xff0000).await;
+	object_notification(sim.proxy(), message, SELF_ID + 1, 0x00ff00).await;
+	sim.assert_hp(500.0);
+}
+
+#[tokio::test]
+async fn sync() {
+	let mut sim = Sim::new(1000, 0, 0);
+	sim.proxy().state.common.server_tick_id = 5;
+
+	// the server doesn't know about the hit yet
+	sim.hit(300).await;
+	sim.assert_hp(700.0);
+
+	// not before the client has acknowledged 10 more ticks
+	sim.ack(15, 0).await;
+	sim.assert_hp(700.0);
+
+	// the server had a different idea about the damage
+	sim.stats().hp = 710;
+	sim.ack(16, 0).await;
+	sim.assert_hp(710.0);
+
+	// a tick with the server's HP lower than ours syncs right away
+	sim.stats().hp = 690;
+	sim.tick(0).await;
+	sim.assert_hp(690.0);
+}
+
+#[tokio::test]
+async fn nexus_once() {
+	let mut sim = Sim::new(100, 0, 0);
+
+	sim.hit(90).await;
+	// still below the threshold while waiting for the server to send us away
+	sim.hit(5).await;
+	for _ in 0..3 {
+		sim.tick(100).await;
+	}
+
+	let nexus_events = sim
+		.proxy()
+		.state
+		.autonexus
+		.timeline
+		.events
+		.iter()
+		.filter(|event| matches!(event.kind, EventKind::Nexus { .. }))
+		.count();
+	assert_eq!(nexus_events, 1);
+}
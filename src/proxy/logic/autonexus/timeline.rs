@@ -0,0 +1,284 @@
+//! The last few seconds of everything that changed the simulated HP, to see afterwards
+//! why autonexus did or didn't fire.
+
+use crate::{
+	damage_monitor_http_server::{Timeline as TimelinePage, TimelineRow},
+	proxy::{Proxy, logic::common::bullets::BulletId},
+	util::{BLUE, create_notification},
+};
+use std::{collections::VecDeque, fmt::Write};
+use tracing::{error, info};
+
+// how much of the past to remember, in milliseconds of game time
+const KEEP_MS: u32 = 30_000;
+
+#[derive(Default)]
+pub struct Timeline {
+	pub(super) events: VecDeque<Event>,
+	// autonexus fires on every hit while below the threshold, only the first one is interesting
+	dumped: bool,
+}
+
+pub(super) struct Event {
+	pub time: u32,
+	pub kind: EventKind,
+	// simulated and server HP after the event
+	pub hp: f32,
+	pub server_hp: i64,
+}
+
+/// What dealt the damage
+pub enum Cause {
+	Bullet {
+		id: BulletId,
+		bullet_type: u8,
+		enemy: Enemy,
+	},
+	Aoe {
+		enemy: Enemy,
+	},
+	Ground,
+}
+
+pub struct Enemy {
+	pub object_type: u32,
+	pub name: String,
+}
+
+impl Enemy {
+	pub fn new(proxy: &Proxy, object_type: u32) -> Self {
+		let name = match proxy.rotmguard.assets.objects.get(&object_type) {
+			Some(object) => object.name.clone(),
+			None => format!("unknown enemy {object_type}"),
+		};
+
+		Self { object_type, name }
+	}
+}
+
+pub enum EventKind {
+	/// 0 damage if invulnerable or invincible
+	Damage {
+		cause: Cause,
+		damage: i64,
+		/// (condition, condition2) at the time, including the ones just inflicted
+		conditions: (u64, u64),
+	},
+	Inflicted {
+		conditions: String,
+		duration: f32,
+	},
+	Heal {
+		amount: i64,
+	},
+	Regen {
+		amount: f32,
+	},
+	/// bleeding, drowning, freezing
+	DamageOverTime {
+		cause: &'static str,
+		amount: f32,
+	},
+	/// simulated HP replaced with the server's
+	Sync {
+		delta: f32,
+	},
+	Nexus {
+		threat: i64,
+	},
+	/// the server didn't send us away in time
+	EscapeRetry {
+		attempt: u32,
+	},
+	Fallback {
+		server: String,
+	},
+	Death,
+}
+
+/// Adds an event that just happened, with the HP as it is now
+pub fn record(proxy: &mut Proxy, kind: EventKind) {
+	let time = proxy.state.common.time;
+	let server_hp = proxy.state.common.objects.get_self().stats.hp;
+	let hp = proxy.state.autonexus.hp;
+
+	let events = &mut proxy.state.autonexus.timeline.events;
+	while events
+		.front()
+		.is_some_and(|event| time.wrapping_sub(event.time) > KEEP_MS)
+	{
+		events.pop_front();
+	}
+
+	events.push_back(Event {
+		time,
+		kind,
+		hp,
+		server_hp,
+	});
+}
+
+/// Publishes the timeline on the http server and writes it to a file if enabled.
+/// Only once per connection when autonexus fires, every time for deaths.
+pub async fn dump(proxy: &mut Proxy, reason: &str, death: bool) {
+	if proxy.state.autonexus.timeline.dumped && !death {
+		return;
+	}
+	proxy.state.autonexus.timeline.dumped = true;
+
+	let page = page(
+		&proxy.state.autonexus.timeline,
+		proxy.state.common.time,
+		reason,
+	);
+
+	if *proxy
+		.rotmguard
+		.config
+		.settings
+		.autonexus_log
+		.lock()
+		.unwrap()
+	{
+		let path = format!(
+			"autonexus-{}.txt",
+			chrono::Local::now().format("%F_%H-%M-%S")
+		);
+		match tokio::fs::write(&path, text(&page)).await {
+			Ok(()) => info!("Autonexus timeline written to {path}"),
+			Err(e) => error!("Error writing autonexus timeline to {path}: {e}"),
+		}
+	}
+
+	if let Some(id) = proxy.rotmguard.damage_monitor_http.add_timeline(page) {
+		let port = proxy.rotmguard.damage_monitor_http.port();
+		let url = format!("http://127.0.0.1:{port}/autonexus/{id}");
+		info!("Autonexus timeline at {url}");
+		proxy
+			.send_client(create_notification(
+				&format!("Autonexus timeline at\n{url}"),
+				BLUE,
+			))
+			.await;
+	}
+}
+
+fn page(timeline: &Timeline, now: u32, reason: &str) -> TimelinePage {
+	let rows = timeline
+		.events
+		.iter()
+		.map(|event| {
+			let (description, change) = describe(&event.kind);
+			TimelineRow {
+				time: format!("-{:.2}s", now.wrapping_sub(event.time) as f32 / 1000.0),
+				description,
+				change,
+				hp: event.hp.round() as i64,
+				server_hp: event.server_hp,
+			}
+		})
+		.collect();
+
+	TimelinePage {
+		reason: reason.to_owned(),
+		time: chrono::Local::now().format("%F %T").to_string(),
+		rows,
+	}
+}
+
+// (what happened, the change in HP)
+fn describe(kind: &EventKind) -> (String, String) {
+	match kind {
+		EventKind::Damage { cause, damage, .. } => {
+			let description = match cause {
+				Cause::Bullet {
+					id,
+					bullet_type,
+					enemy,
+				} => format!(
+					"Hit by {} (object {}, bullet {} of type {bullet_type})",
+					enemy.name, id.owner_id, id.id
+				),
+				Cause::Aoe { enemy } => format!("Hit by AoE of {}", enemy.name),
+				Cause::Ground => "Ground damage".to_owned(),
+			};
+			(description, format!("-{damage}"))
+		}
+		EventKind::Inflicted {
+			conditions,
+			duration,
+		} => (
+			format!("Inflicted {conditions} for {duration}s"),
+			String::new(),
+		),
+		EventKind::Heal { amount } => ("Healed".to_owned(), format!("+{amount}")),
+		EventKind::Regen { amount } => ("Regeneration".to_owned(), format!("+{amount:.1}")),
+		EventKind::DamageOverTime { cause, amount } => (cause.to_string(), format!("-{amount:.1}")),
+		EventKind::Sync { delta } => ("Synced with server".to_owned(), format!("{delta:+.1}")),
+		EventKind::Nexus { threat: 0 } => ("Autonexus".to_owned(), String::new()),
+		EventKind::Nexus { threat } => (
+			format!("Autonexus, expecting {threat} more damage"),
+			String::new(),
+		),
+		EventKind::EscapeRetry { attempt } => {
+			(format!("Escape resent, attempt {attempt}"), String::new())
+		}
+		EventKind::Fallback { server } => (
+			format!("Escape failed, reconnecting to {server}"),
+			String::new(),
+		),
+		EventKind::Death => ("Died".to_owned(), String::new()),
+	}
+}
+
+fn text(page: &TimelinePage) -> String {
+	let mut text = format!("{} at {}\n\n", page.reason, page.time);
+	for row in &page.rows {
+		let _ = writeln!(
+			text,
+			"{:>8} {:>7} {:>6} HP (server {:>5})  {}",
+			row.time, row.change, row.hp, row.server_hp, row.description
+		);
+	}
+
+	text
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::proxy::replay::{Replay, test_rotmguard};
+
+	#[tokio::test]
+	async fn forgets_old_events() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+		let proxy = replay.proxy();
+		proxy.state.autonexus.hp = 100.0;
+
+		record(proxy, EventKind::Heal { amount: 10 });
+		proxy.state.common.time = 20_000;
+		proxy.state.autonexus.hp = 50.0;
+		record(
+			proxy,
+			EventKind::Damage {
+				cause: Cause::Ground,
+				damage: 50,
+				conditions: (0, 0),
+			},
+		);
+		proxy.state.common.time = 40_000;
+		record(proxy, EventKind::Nexus { threat: 0 });
+
+		let page = page(&proxy.state.autonexus.timeline, 40_000, "Autonexus");
+		let rows: Vec<_> = page
+			.rows
+			.iter()
+			.map(|row| (row.time.as_str(), row.change.as_str(), row.hp))
+			.collect();
+		assert_eq!(rows, [("-20.00s", "-50", 50), ("-0.00s", "", 50)]);
+		assert_eq!(
+			text(&page).lines().last(),
+			Some("  -0.00s             50 HP (server     0)  Autonexus")
+		);
+	}
+}
@@ -0,0 +1,360 @@
+//! Chat commands.
+//!
+//! Each feature declares its commands in [`Feature::COMMANDS`](super::feature::Feature::COMMANDS).
+//! The arguments are parsed and checked here, so the features only ever see valid input,
+//! and `/help` is generated from the same declarations.
+
+use super::{errors, feature};
+use crate::{
+	config::Threshold,
+	damage_monitor_http_server::DamageMonitorHttp,
+	proxy::Proxy,
+	util::{BLUE, RED, create_notification},
+};
+use std::fmt::Write;
+
+// how many commands fit in one /help notification
+const HELP_PAGE_SIZE: usize = 6;
+
+pub struct Command {
+	/// Without the `/`
+	pub name: &'static str,
+	pub aliases: &'static [&'static str],
+	/// Optional arguments must come after the required ones
+	pub args: &'static [Arg],
+	pub description: &'static str,
+	pub gate: Gate,
+}
+
+pub struct Arg {
+	pub name: &'static str,
+	/// Tried in this order, the first one that fits is used
+	pub kinds: &'static [ArgKind],
+	pub optional: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ArgKind {
+	/// Any single word
+	Word,
+	/// A whole number, not negative
+	Int,
+	/// HP or a percentage of max HP, like "20" or "30%"
+	Threshold,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ArgValue<'a> {
+	Word(&'a str),
+	Int(usize),
+	Threshold(Threshold),
+}
+
+/// What else a command needs to be usable, besides its feature not being disabled
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Gate {
+	Always,
+	/// The damage monitor http server, which is only running if enabled in rotmguard.toml
+	DamageMonitor,
+}
+
+/// Parsed arguments, in the same order as [`Command::args`].
+/// Optional arguments that were not given are None.
+#[derive(Debug, PartialEq)]
+pub struct Args<'a>(Vec<Option<ArgValue<'a>>>);
+
+impl<'a> Args<'a> {
+	pub fn get(&self, i: usize) -> Option<ArgValue<'a>> {
+		self.0.get(i).copied().flatten()
+	}
+	pub fn word(&self, i: usize) -> Option<&'a str> {
+		match self.get(i) {
+			Some(ArgValue::Word(x)) => Some(x),
+			_ => None,
+		}
+	}
+	pub fn int(&self, i: usize) -> Option<usize> {
+		match self.get(i) {
+			Some(ArgValue::Int(x)) => Some(x),
+			_ => None,
+		}
+	}
+	pub fn threshold(&self, i: usize) -> Option<Threshold> {
+		match self.get(i) {
+			Some(ArgValue::Threshold(x)) => Some(x),
+			_ => None,
+		}
+	}
+}
+
+impl ArgKind {
+	fn parse(self, word: &str) -> Result<ArgValue<'_>, String> {
+		match self {
+			ArgKind::Word => Ok(ArgValue::Word(word)),
+			ArgKind::Int => word
+				.parse()
+				.map(ArgValue::Int)
+				.map_err(|_| format!("{word} is not a whole number")),
+			ArgKind::Threshold => word.parse().map(ArgValue::Threshold),
+		}
+	}
+}
+
+impl Arg {
+	fn parse<'a>(&self, word: &'a str) -> Result<ArgValue<'a>, String> {
+		let mut error = format!("Invalid {}", self.name);
+		for kind in self.kinds {
+			match kind.parse(word) {
+				Ok(value) => return Ok(value),
+				Err(e) => error = e,
+			}
+		}
+
+		Err(error)
+	}
+}
+
+impl Gate {
+	/// Err with the reason if the command can't be used right now
+	fn check(self, proxy: &Proxy) -> Result<(), &'static str> {
+		match self {
+			Gate::Always => Ok(()),
+			Gate::DamageMonitor => match proxy.rotmguard.damage_monitor_http {
+				DamageMonitorHttp::Disabled => {
+					Err("The damage monitor is disabled in rotmguard.toml")
+				}
+				DamageMonitorHttp::Enabled { .. } => Ok(()),
+			},
+		}
+	}
+}
+
+impl Command {
+	/// For example `/autonexus [HP]`
+	pub fn usage(&self) -> String {
+		let mut usage = format!("/{}", self.name);
+		for arg in self.args {
+			if arg.optional {
+				write!(usage, " [{}]", arg.name).unwrap();
+			} else {
+				write!(usage, " <{}>", arg.name).unwrap();
+			}
+		}
+
+		usage
+	}
+	fn matches(&self, name: &str) -> bool {
+		self.name.eq_ignore_ascii_case(name)
+			|| self.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+	}
+	pub fn parse_args<'a>(&self, words: &[&'a str]) -> Result<Args<'a>, String> {
+		if words.len() > self.args.len() {
+			return Err("Too many arguments".to_owned());
+		}
+
+		let mut values = Vec::with_capacity(self.args.len());
+		for (i, arg) in self.args.iter().enumerate() {
+			let value = match words.get(i) {
+				None if arg.optional => None,
+				None => return Err(format!("Missing {}", arg.name)),
+				Some(word) => Some(arg.parse(word)?),
+			};
+			values.push(value);
+		}
+
+		Ok(Args(values))
+	}
+}
+
+/// Returns true if the text was one of our commands, in which case it's not sent to the server.
+/// Anything else (including the game's own commands) is left alone.
+pub async fn handle(proxy: &mut Proxy, text: &str) -> bool {
+	// must start with `/`, immediately followed by a letter or a number
+	let Some(text) = text.strip_prefix('/') else {
+		return false;
+	};
+	if !text.starts_with(char::is_alphanumeric) {
+		return false;
+	}
+
+	let mut words = text.split_whitespace();
+	let name = words.next().unwrap_or_default();
+
+	let Some((feature, command)) = feature::commands()
+		.into_iter()
+		.find(|(_, command)| command.matches(name))
+	else {
+		return false;
+	};
+
+	let words: Vec<&str> = words.collect();
+	let args = match command.parse_args(&words) {
+		Ok(args) => args,
+		Err(e) => {
+			let notification =
+				create_notification(&format!("{e}\nUsage: {}", command.usage()), RED);
+			proxy.send_client(notification).await;
+			return true;
+		}
+	};
+
+	if !errors::is_enabled(proxy, feature) {
+		let notification = create_notification(
+			&format!("{} is disabled for this connection", feature.name()),
+			RED,
+		);
+		proxy.send_client(notification).await;
+		return true;
+	}
+
+	if let Err(reason) = command.gate.check(proxy) {
+		proxy.send_client(create_notification(reason, RED)).await;
+		return true;
+	}
+
+	feature::run_command(proxy, feature, command, &args).await;
+
+	true
+}
+
+/// `/help [command|page]`
+pub async fn help(proxy: &mut Proxy, args: &Args<'_>) {
+	let (text, color) = match (args.int(0), args.word(0)) {
+		(Some(page), _) => (help_page(page), BLUE),
+		(None, Some(name)) => command_help(name.trim_start_matches('/')),
+		(None, None) => (help_page(1), BLUE),
+	};
+
+	proxy.send_client(create_notification(&text, color)).await;
+}
+
+fn help_page(page: usize) -> String {
+	let commands = feature::commands();
+	let pages = commands.len().div_ceil(HELP_PAGE_SIZE);
+	let page = page.clamp(1, pages);
+
+	let mut text = format!("Commands ({page}/{pages}):");
+	for (_, command) in commands
+		.iter()
+		.skip((page - 1) * HELP_PAGE_SIZE)
+		.take(HELP_PAGE_SIZE)
+	{
+		write!(text, "\n/{} - {}", command.name, command.description).unwrap();
+	}
+	if page < pages {
+		write!(text, "\n/help {} for more", page + 1).unwrap();
+	}
+
+	text
+}
+
+fn command_help(name: &str) -> (String, u32) {
+	let Some((_, command)) = feature::commands()
+		.into_iter()
+		.find(|(_, command)| command.matches(name))
+	else {
+		return (format!("Unknown command /{name}"), RED);
+	};
+
+	let mut text = format!("{}\n{}", command.usage(), command.description);
+	if !command.aliases.is_empty() {
+		let aliases: Vec<String> = command.aliases.iter().map(|a| format!("/{a}")).collect();
+		write!(text, "\nAlso: {}", aliases.join(", ")).unwrap();
+	}
+
+	(text, BLUE)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const COMMAND: Command = Command {
+		name: "test",
+		aliases: &["t"],
+		args: &[
+			Arg {
+				name: "name",
+				kinds: &[ArgKind::Word],
+				optional: false,
+			},
+			Arg {
+				name: "amount",
+				kinds: &[ArgKind::Int],
+				optional: true,
+			},
+			Arg {
+				name: "HP",
+				kinds: &[ArgKind::Int, ArgKind::Threshold],
+				optional: true,
+			},
+		],
+		description: "",
+		gate: Gate::Always,
+	};
+
+	#[test]
+	fn usage() {
+		assert_eq!(COMMAND.usage(), "/test <name> [amount] [HP]");
+	}
+
+	#[test]
+	fn names() {
+		assert!(COMMAND.matches("test"));
+		assert!(COMMAND.matches("TEST"));
+		assert!(COMMAND.matches("t"));
+		assert!(!COMMAND.matches("tes"));
+	}
+
+	#[test]
+	fn args() {
+		let args = COMMAND.parse_args(&["x", "5", "30%"]).unwrap();
+		assert_eq!(args.word(0), Some("x"));
+		assert_eq!(args.int(1), Some(5));
+		assert_eq!(args.threshold(2), Some(Threshold::Percent(30.0)));
+
+		// the first kind that fits wins
+		let args = COMMAND.parse_args(&["x", "5", "20"]).unwrap();
+		assert_eq!(args.int(2), Some(20));
+		assert_eq!(args.threshold(2), None);
+
+		let args = COMMAND.parse_args(&["x"]).unwrap();
+		assert_eq!(args.int(1), None);
+
+		assert!(COMMAND.parse_args(&[]).is_err());
+		assert!(COMMAND.parse_args(&["x", "1", "2", "3"]).is_err());
+		assert_eq!(
+			COMMAND.parse_args(&["x", "-5"]),
+			Err("-5 is not a whole number".to_owned())
+		);
+		// the error of the last kind tried
+		assert_eq!(
+			COMMAND.parse_args(&["x", "1", "lots"]),
+			Err("lots is not a whole number or a percentage like 30%".to_owned())
+		);
+	}
+
+	#[test]
+	fn help_pages() {
+		let commands = feature::commands();
+
+		let mut listed = 0;
+		let mut page = 1;
+		loop {
+			let text = help_page(page);
+			listed += text
+				.lines()
+				.filter(|line| line.starts_with('/') && line.contains(" - "))
+				.count();
+			if !text.ends_with("for more") {
+				break;
+			}
+			page += 1;
+		}
+		assert_eq!(listed, commands.len());
+
+		// out of range pages show the last one
+		assert_eq!(help_page(page + 10), help_page(page));
+		assert_eq!(help_page(0), help_page(1));
+	}
+}
@@ -1,18 +1,110 @@
 //! generic game state used across different cheats
 
+use super::{
+	errors::FeatureId,
+	feature::{Feature, Verdict},
+};
+use crate::{
+	protocol::{C2SPacket, MoveRecord, ObjectStatus, S2CPacket},
+	proxy::Proxy,
+};
+use anyhow::Result;
 use bullets::Bullets;
+use either::Either;
 use objects::Objects;
 
 pub mod bullets;
 pub mod objects;
 
-pub use bullets::{enemyshoot, playershoot, serverplayershoot, set_rng_seed};
+pub use bullets::{enemyshoot, forget_landed, playershoot, serverplayershoot, set_rng_seed};
 pub use objects::{add_object, object_int_stat, object_str_stat, remove_object};
 
 #[derive(Default)]
 pub struct Common {
 	pub my_position: (f32, f32),
 	pub server_tick_id: u32,
+	/// Game time in milliseconds, the sum of all tick times
+	pub time: u32,
 	pub bullets: Bullets,
 	pub objects: Objects,
 }
+
+impl Feature for Common {
+	const ID: FeatureId = FeatureId::Common;
+
+	async fn on_c2s(proxy: &mut Proxy, packet: &mut C2SPacket<'_>, _: &mut Verdict) -> Result<()> {
+		match packet {
+			C2SPacket::Move(r#move) => {
+				proxy.state.common.my_position = match r#move.records.last() {
+					Some(&MoveRecord { pos_x, pos_y, .. }) => (pos_x, pos_y),
+					None => (0.0, 0.0),
+				};
+			}
+			C2SPacket::PlayerShoot(shoot) => {
+				playershoot(
+					proxy,
+					shoot.bullet_id,
+					shoot.weapon_id as u32,
+					shoot.projectile_type,
+				);
+			}
+			_ => {}
+		}
+
+		Ok(())
+	}
+	async fn on_s2c(proxy: &mut Proxy, packet: &mut S2CPacket<'_>, _: &mut Verdict) -> Result<()> {
+		match packet {
+			S2CPacket::MapInfo(map_info) => set_rng_seed(proxy, map_info.seed),
+			// The packet is used when joining to tell the client its object id
+			S2CPacket::CreateSuccess(create_success) => {
+				proxy.state.common.objects.self_id = create_success.object_id;
+			}
+			S2CPacket::EnemyShoot(shoot) => {
+				enemyshoot(proxy, shoot)?;
+			}
+			S2CPacket::ServerPlayerShoot(shoot) => {
+				serverplayershoot(
+					proxy,
+					shoot.bullet_id,
+					shoot.shooter_id,
+					shoot.summoner_id,
+					shoot.damage,
+					shoot.bullet_type,
+					shoot.bullet_count,
+				);
+			}
+			S2CPacket::Update(update) => {
+				// first remove the old objects, since the same ids may be reused by the new ones
+				for &object_id in &update.to_remove {
+					remove_object(proxy, object_id);
+				}
+				for object in &update.new_objects {
+					add_object(proxy, object.status.object_id, object.object_type);
+					object_stats(proxy, &object.status).await;
+				}
+			}
+			S2CPacket::NewTick(tick) => {
+				proxy.state.common.server_tick_id = tick.tick_id;
+				proxy.state.common.time = proxy.state.common.time.wrapping_add(tick.tick_time);
+				forget_landed(proxy);
+
+				for status in &tick.statuses {
+					object_stats(proxy, status).await;
+				}
+			}
+			_ => {}
+		}
+
+		Ok(())
+	}
+}
+
+async fn object_stats(proxy: &mut Proxy, status: &ObjectStatus<'_>) {
+	for stat in &status.stats {
+		match stat.data {
+			Either::Left(s) => object_str_stat(proxy, status.object_id, stat.stat_type, s),
+			Either::Right(i) => object_int_stat(proxy, status.object_id, stat.stat_type, i).await,
+		}
+	}
+}
@@ -1,5 +1,6 @@
 use crate::{
 	assets::{EnchantmentEffect, ProjectileInfo},
+	protocol::EnemyShoot,
 	proxy::Proxy,
 	util::CONDITION_BITFLAG,
 };
@@ -10,6 +11,7 @@ use std::num::NonZeroUsize;
 use tracing::error;
 
 mod rng;
+pub mod trajectory;
 
 // How many bullets to keep track of at the same time, since we are not detecting when they disappear
 const BULLETS_CACHE: usize = 100_000; // just to be safe
@@ -17,6 +19,8 @@ const BULLETS_CACHE: usize = 100_000; // just to be safe
 pub struct Bullets {
 	rng: Rng,
 	pub cache: LruCache<BulletId, Bullet>,
+	/// Enemy bullets that may still be flying. Forgotten when their lifetime ends
+	pub flying: Vec<BulletId>,
 }
 
 // this game is so coherent that it has duplicating bullet ids, you need them together with the owner id
@@ -27,12 +31,24 @@ pub struct BulletId {
 	pub owner_id: u32,
 }
 
-#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub struct Bullet {
 	pub damage: u16,
 	pub summoner_id: Option<u32>,
 	pub object_type: u32,
 	pub bullet_type: u8,
+	/// Only known for enemy bullets
+	pub shot: Option<Shot>,
+}
+
+/// Where, when and which way a bullet was shot
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Shot {
+	pub start: (f32, f32),
+	/// radians
+	pub angle: f32,
+	/// Game time (see [`Common::time`](super::Common::time))
+	pub time: u32,
 }
 
 impl Default for Bullets {
@@ -40,6 +56,7 @@ impl Default for Bullets {
 		Self {
 			rng: Rng::new(0),
 			cache: LruCache::new(NonZeroUsize::new(BULLETS_CACHE).unwrap()),
+			flying: Vec::new(),
 		}
 	}
 }
@@ -69,14 +86,18 @@ pub fn set_rng_seed(proxy: &mut Proxy, seed: u32) {
 	proxy.state.common.bullets.rng = Rng::new(seed);
 }
 
-pub fn enemyshoot(
-	proxy: &mut Proxy,
-	bullet_id: u16,
-	owner_id: u32,
-	mut bullet_type: u8,
-	damage: i16,
-	numshots: u8,
-) -> Result<()> {
+pub fn enemyshoot(proxy: &mut Proxy, shoot: &EnemyShoot) -> Result<()> {
+	let &EnemyShoot {
+		bullet_id,
+		owner_id,
+		mut bullet_type,
+		pos_x,
+		pos_y,
+		angle,
+		damage,
+		multishot,
+	} = shoot;
+
 	if bullet_type as i8 == -1 {
 		bullet_type = 0;
 	}
@@ -87,25 +108,59 @@ pub fn enemyshoot(
 		None => return Ok(()),
 	};
 
-	// create N bullets with incremental IDs where N is the number of shots
+	let (numshots, angle_between_shots) = multishot.unwrap_or((1, 0.0));
+
+	// create N bullets with incremental IDs where N is the number of shots,
+	// each one turned a bit more
 	for i in 0..numshots {
+		let id = BulletId {
+			id: bullet_id + i as u16,
+			owner_id,
+		};
+		let shot = Shot {
+			start: (pos_x, pos_y),
+			angle: angle + angle_between_shots * i as f32,
+			time: proxy.state.common.time,
+		};
 		proxy.state.common.bullets.cache.put(
-			BulletId {
-				id: bullet_id + i as u16,
-				owner_id,
-			},
+			id,
 			Bullet {
 				damage: damage as u16,
 				summoner_id: None,
 				object_type,
 				bullet_type,
+				shot: Some(shot),
 			},
 		);
+		proxy.state.common.bullets.flying.push(id);
 	}
 
 	Ok(())
 }
 
+/// Forgets the enemy bullets that have already disappeared
+pub fn forget_landed(proxy: &mut Proxy) {
+	let now = proxy.state.common.time;
+	let bullets = &proxy.state.common.bullets;
+
+	let flying = bullets
+		.flying
+		.iter()
+		.copied()
+		.filter(|id| {
+			let Some(bullet) = bullets.cache.peek(id) else {
+				return false;
+			};
+			match (bullet.shot, bullet.get_properties(proxy)) {
+				(Some(shot), Ok(info)) => now.wrapping_sub(shot.time) <= info.movement.lifetime_ms,
+				_ => false,
+			}
+		})
+		.collect();
+
+	proxy.state.common.bullets.flying = flying;
+}
+
 pub fn playershoot(proxy: &mut Proxy, bullet_id: u16, weapon_id: u32, mut projectile_type: u8) {
 	// 🙏
 	if projectile_type as i8 == -1 {
@@ -177,6 +232,7 @@ pub fn playershoot(proxy: &mut Proxy, bullet_id: u16, weapon_id: u32, mut projec
 			summoner_id: None,
 			object_type: weapon_id,
 			bullet_type: projectile_type,
+			shot: None,
 		},
 	);
 }
@@ -216,6 +272,7 @@ pub fn serverplayershoot(
 				summoner_id: Some(summoner_id),
 				object_type: shooter_type,
 				bullet_type: projectile_type,
+				shot: None,
 			},
 		);
 	}
@@ -0,0 +1,238 @@
+//! Where a bullet is at a given time after being shot, the same way the client calculates it.
+
+use super::Shot;
+use crate::assets::{Movement, Path, ProjectileInfo};
+use std::f32::consts::{PI, SQRT_2};
+
+/// Position of the bullet `elapsed` milliseconds after it was shot
+pub fn position(info: &ProjectileInfo, bullet_id: u16, shot: &Shot, elapsed: f32) -> (f32, f32) {
+	let movement = &info.movement;
+	let lifetime = movement.lifetime_ms as f32;
+	let (mut x, mut y) = shot.start;
+	let mut distance = distance(movement, elapsed);
+	// every other bullet goes the other way, so that multishots look symmetric
+	let phase = if bullet_id.is_multiple_of(2) { 0.0 } else { PI };
+
+	match movement.path {
+		Path::Wavy => {
+			let theta = shot.angle + (PI / 64.0) * (phase + 6.0 * PI * elapsed / 1000.0).sin();
+			x += distance * theta.cos();
+			y += distance * theta.sin();
+		}
+		Path::Parametric { magnitude } => {
+			let t = elapsed / lifetime * 2.0 * PI;
+			let px = t.sin()
+				* if bullet_id.is_multiple_of(2) {
+					-1.0
+				} else {
+					1.0
+				};
+			let py = (2.0 * t).sin() * if bullet_id % 4 < 2 { 1.0 } else { -1.0 };
+			let (sin, cos) = shot.angle.sin_cos();
+			x += (px * cos - py * sin) * magnitude;
+			y += (px * sin + py * cos) * magnitude;
+		}
+		Path::Straight {
+			boomerang,
+			amplitude,
+			frequency,
+		} => {
+			if boomerang {
+				let halfway = self::distance(movement, lifetime / 2.0);
+				if distance > halfway {
+					distance = halfway - (distance - halfway);
+				}
+			}
+			x += distance * shot.angle.cos();
+			y += distance * shot.angle.sin();
+
+			if amplitude != 0.0 {
+				let deflection =
+					amplitude * (phase + elapsed / lifetime * frequency * 2.0 * PI).sin();
+				x += deflection * (shot.angle + PI / 2.0).cos();
+				y += deflection * (shot.angle + PI / 2.0).sin();
+			}
+		}
+	}
+
+	(x, y)
+}
+
+/// How far the bullet can get between `from` and `to` milliseconds after it was shot, measured
+/// from where it is at `from`. Never less than the real distance, so bullets further away than
+/// this from the player can't hit it in that time.
+pub fn reach(info: &ProjectileInfo, from: f32, to: f32) -> f32 {
+	let movement = &info.movement;
+	// the speed only ever goes one way, so it's fastest at one of the ends
+	let fastest = speed(movement, from).abs().max(speed(movement, to).abs());
+	let along = fastest * (to - from);
+
+	match movement.path {
+		// turns by up to PI / 64 to either side
+		Path::Wavy => along + (distance(movement, from).abs() + along) * 2.0 * PI / 64.0,
+		// stays around where it was shot from
+		Path::Parametric { magnitude } => 2.0 * SQRT_2 * magnitude.abs(),
+		Path::Straight { amplitude, .. } => along + 2.0 * amplitude.abs(),
+	}
+}
+
+// per millisecond
+fn speed(movement: &Movement, elapsed: f32) -> f32 {
+	let speed = movement.speed / 10000.0;
+	let delay = movement.acceleration_delay_ms as f32;
+
+	if movement.acceleration == 0.0 || elapsed <= delay {
+		return speed;
+	}
+
+	let acceleration = movement.acceleration / 10000.0 / 1000.0;
+	speed + acceleration * (elapsed - delay).min(until_clamp(movement, acceleration))
+}
+
+// how long after starting to accelerate the speed is clamped
+fn until_clamp(movement: &Movement, acceleration: f32) -> f32 {
+	match movement.speed_clamp {
+		Some(clamp) => ((clamp / 10000.0 - movement.speed / 10000.0) / acceleration).max(0.0),
+		None => f32::INFINITY,
+	}
+}
+
+// how far along its path the bullet is, in tiles
+fn distance(movement: &Movement, elapsed: f32) -> f32 {
+	// per millisecond
+	let speed = movement.speed / 10000.0;
+	let delay = movement.acceleration_delay_ms as f32;
+
+	if movement.acceleration == 0.0 || elapsed <= delay {
+		return elapsed * speed;
+	}
+
+	// per millisecond, per millisecond
+	let acceleration = movement.acceleration / 10000.0 / 1000.0;
+	let accelerating = elapsed - delay;
+	let until_clamp = until_clamp(movement, acceleration);
+
+	let t = accelerating.min(until_clamp);
+	let mut distance = delay * speed + speed * t + acceleration * t * t / 2.0;
+	if accelerating > until_clamp {
+		distance += (speed + acceleration * t) * (accelerating - until_clamp);
+	}
+
+	distance
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use either::Either;
+
+	fn info(path: Path) -> ProjectileInfo {
+		ProjectileInfo {
+			damage: Either::Left(10),
+			armor_piercing: false,
+			inflicts: Vec::new(),
+			multi_hit: false,
+			passes_cover: false,
+			size: 100,
+			movement: Movement {
+				speed: 100.0, // 10 tiles per second
+				lifetime_ms: 1000,
+				acceleration: 0.0,
+				acceleration_delay_ms: 0,
+				speed_clamp: None,
+				path,
+			},
+		}
+	}
+
+	fn line(boomerang: bool, amplitude: f32) -> Path {
+		Path::Straight {
+			boomerang,
+			amplitude,
+			frequency: 1.0,
+		}
+	}
+
+	fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+		(a.0 - b.0).abs() < 0.001 && (a.1 - b.1).abs() < 0.001
+	}
+
+	#[test]
+	fn motions() {
+		let shot = Shot {
+			start: (1.0, 2.0),
+			angle: PI / 2.0,
+			time: 0,
+		};
+
+		let straight = info(line(false, 0.0));
+		assert!(close(position(&straight, 0, &shot, 500.0), (1.0, 7.0)));
+
+		let boomerang = info(line(true, 0.0));
+		assert!(close(position(&boomerang, 0, &shot, 500.0), (1.0, 7.0)));
+		assert!(close(position(&boomerang, 0, &shot, 1000.0), (1.0, 2.0)));
+
+		// a quarter period in, fully deflected sideways
+		let amplitude = info(line(false, 0.5));
+		assert!(close(position(&amplitude, 0, &shot, 250.0), (0.5, 4.5)));
+
+		// the figure-eight comes back to the start
+		let parametric = info(Path::Parametric { magnitude: 3.0 });
+		assert!(close(position(&parametric, 0, &shot, 0.0), shot.start));
+		assert!(close(position(&parametric, 0, &shot, 500.0), shot.start));
+	}
+
+	#[test]
+	fn reaches() {
+		let shot = Shot {
+			start: (1.0, 2.0),
+			angle: 0.3,
+			time: 0,
+		};
+
+		let mut accelerating = info(line(true, 0.0));
+		accelerating.movement.acceleration = -100.0;
+		accelerating.movement.acceleration_delay_ms = 200;
+		accelerating.movement.speed_clamp = Some(-50.0);
+
+		let infos = [
+			info(line(false, 0.0)),
+			info(line(true, 0.7)),
+			info(Path::Wavy),
+			info(Path::Parametric { magnitude: 3.0 }),
+			accelerating,
+		];
+		for info in &infos {
+			for bullet_id in 0..4 {
+				for from in (0..1000).step_by(100) {
+					let to = from + 600;
+					let reach = reach(info, from as f32, to as f32);
+					let (x, y) = position(info, bullet_id, &shot, from as f32);
+					for t in from..=to {
+						let (tx, ty) = position(info, bullet_id, &shot, t as f32);
+						assert!((tx - x).hypot(ty - y) <= reach + 0.001);
+					}
+				}
+			}
+		}
+
+		// half a second at 10 tiles per second
+		assert!((reach(&infos[0], 100.0, 600.0) - 5.0).abs() < 0.001);
+	}
+
+	#[test]
+	fn acceleration() {
+		let mut movement = info(line(false, 0.0)).movement;
+		movement.acceleration = 100.0; // +10 tiles per second every second
+		movement.acceleration_delay_ms = 500;
+
+		assert!((distance(&movement, 500.0) - 5.0).abs() < 0.001);
+		// 5 + 10 * 1 + 10 * 1 * 1 / 2
+		assert!((distance(&movement, 1500.0) - 20.0).abs() < 0.001);
+
+		// reaches 15 tiles per second after half a second
+		movement.speed_clamp = Some(150.0);
+		// 5 + (10 * 0.5 + 10 * 0.5 * 0.5 / 2) + 15 * 0.5
+		assert!((distance(&movement, 1500.0) - 18.75).abs() < 0.001);
+	}
+}
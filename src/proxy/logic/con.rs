@@ -1,24 +1,43 @@
+use super::{
+	commands::{Arg, ArgKind, Args, Command, Gate},
+	errors::FeatureId,
+	feature::Feature,
+};
 use crate::{
 	proxy::Proxy,
-	util::{BLUE, RED, create_reconnect, static_notification},
+	util::{RED, create_reconnect, static_notification},
 };
+use anyhow::Result;
 use bytes::Bytes;
 
-pub async fn con(proxy: &mut Proxy, mut args: impl Iterator<Item = &str>) {
-	let server = match args.next() {
-		Some(s) => s,
-		None => {
-			proxy.send_client(usage_notification()).await;
-			return;
+/// `/con <server>` reconnects to another server
+pub struct Con;
+
+impl Feature for Con {
+	const ID: FeatureId = FeatureId::Con;
+	const COMMANDS: &'static [Command] = &[Command {
+		name: "con",
+		aliases: &[],
+		args: &[Arg {
+			name: "server",
+			kinds: &[ArgKind::Word],
+			optional: false,
+		}],
+		description: "Connects to a server by its short name. Example: /con eue",
+		gate: Gate::Always,
+	}];
+
+	async fn on_command(proxy: &mut Proxy, _: &Command, args: &Args<'_>) -> Result<()> {
+		if let Some(server) = args.word(0) {
+			con(proxy, server).await;
 		}
-	};
 
-	// if extra args (we already took the server arg)
-	if args.count() > 0 {
-		proxy.send_client(usage_notification()).await;
-		return;
+		Ok(())
 	}
+}
 
+/// Returns whether the client was sent to the server, the player is told if the name is unknown
+pub async fn con(proxy: &mut Proxy, server: &str) -> bool {
 	match proxy
 		.rotmguard
 		.rotmg_servers
@@ -27,17 +46,17 @@ pub async fn con(proxy: &mut Proxy, mut args: impl Iterator<Item = &str>) {
 		Some(ip) => {
 			let packet = create_reconnect("have fun :)", ip, 2050, 0xfffffffe, 0xffffffff, &[]);
 			proxy.send_client(packet).await;
+
+			true
 		}
 		None => {
 			proxy.send_client(invalid_server_notification()).await;
+
+			false
 		}
 	}
 }
 
-fn usage_notification() -> Bytes {
-	static_notification!("Usage: /con <short server name>. Example: /con eue", BLUE)
-}
-
 fn invalid_server_notification() -> Bytes {
 	static_notification!("Invalid server name. Examples: eusw, use, eun, a, aus", RED,)
 }
@@ -1,9 +1,20 @@
-use super::common::{bullets::BulletId, objects::Item};
+use super::{
+	commands::{Arg, ArgKind, ArgValue, Args, Command, Gate},
+	common::{
+		bullets::{Bullet, BulletId},
+		objects::Item,
+	},
+	errors::FeatureId,
+	feature::{Feature, Verdict},
+};
 use crate::{
 	Rotmguard,
+	protocol::{C2SPacket, NewTick, NotificationKind, ObjectStatus, S2CPacket},
 	proxy::Proxy,
-	util::{CONDITION_BITFLAG, CONDITION2_BITFLAG, GREEN, RED, create_notification},
+	util::{CONDITION_BITFLAG, CONDITION2_BITFLAG, GREEN, RED, STAT_TYPE, create_notification},
 };
+use anyhow::Result;
+use either::Either;
 use serde::Deserialize;
 use std::{
 	collections::{BTreeMap, HashMap},
@@ -14,6 +25,7 @@ use tracing::{error, warn};
 mod generate_report;
 
 const TAKEN_DAMAGE_CRITERIA: i64 = 1000; // minimum damage an enemy must take to be shown/saved
+const LIVE_INTERVAL_MS: u32 = 500; // how often the live page is updated, in game time
 
 pub struct DamageMonitor {
 	rotmguard: Arc<Rotmguard>,
@@ -22,6 +34,9 @@ pub struct DamageMonitor {
 
 	players: HashMap<u64, Player>,
 	enemies: HashMap<u64, Enemy>,
+
+	// game time of the last update sent to the live page
+	last_live_update: u32,
 }
 
 #[derive(Default)]
@@ -29,6 +44,22 @@ struct Enemy {
 	name: String,
 	object_type: u32,
 	player_damage: BTreeMap<u64, (PlayerStatus, i64)>,
+	// player -> (game time, damage), the damage of a tick added together
+	samples: BTreeMap<u64, Vec<(u32, i64)>>,
+	// player -> what the damage was done with, only known for our own damage
+	sources: BTreeMap<u64, BTreeMap<Source, i64>>,
+	// game times when it became invulnerable after being hit, each one ends a phase
+	invulnerable_at: Vec<u32>,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Source {
+	// item id
+	Weapon(u32),
+	// item id
+	Ability(u32),
+	// object type of the summon
+	Summon(u32),
 }
 
 #[derive(Default)]
@@ -46,11 +77,77 @@ enum PlayerStatus {
 	Nexus,
 }
 
-pub async fn command(proxy: &mut Proxy, mut args: impl Iterator<Item = &str>) {
+impl Feature for DamageMonitor {
+	const ID: FeatureId = FeatureId::DamageMonitor;
+	const COMMANDS: &'static [Command] = &[Command {
+		name: "dmg",
+		aliases: &[],
+		args: &[Arg {
+			name: "report",
+			kinds: &[ArgKind::Int, ArgKind::Word],
+			optional: true,
+		}],
+		description: "Opens the damage report of the current dungeon, or of a previous one by number or name",
+		gate: Gate::DamageMonitor,
+	}];
+
+	async fn on_c2s(proxy: &mut Proxy, packet: &mut C2SPacket<'_>, _: &mut Verdict) -> Result<()> {
+		if let C2SPacket::EnemyHit(hit) = packet {
+			enemyhit(proxy, hit.bullet_id, hit.shooter_id, hit.target_id).await;
+		}
+
+		Ok(())
+	}
+	async fn on_s2c(proxy: &mut Proxy, packet: &mut S2CPacket<'_>, _: &mut Verdict) -> Result<()> {
+		match packet {
+			S2CPacket::MapInfo(map_info) => set_map_name(proxy, map_info.name),
+			S2CPacket::Update(update) => {
+				for &object_id in &update.to_remove {
+					remove_object(proxy, object_id);
+				}
+			}
+			S2CPacket::NewTick(tick) => {
+				for status in &tick.statuses {
+					enemy_conditions(proxy, status);
+				}
+			}
+			S2CPacket::Notification(notification) => {
+				if let NotificationKind::PlayerDeath { json, .. } = notification.kind {
+					death_notification(proxy, json);
+				}
+			}
+			S2CPacket::Damage(damage) => {
+				do_damage(
+					proxy,
+					damage.target_id,
+					damage.damage,
+					damage.owner_id,
+					None,
+				);
+			}
+			_ => {}
+		}
+
+		Ok(())
+	}
+	async fn on_tick(proxy: &mut Proxy, _: &NewTick<'_>) -> Result<()> {
+		live_update(proxy);
+
+		Ok(())
+	}
+	async fn on_command(proxy: &mut Proxy, _: &Command, args: &Args<'_>) -> Result<()> {
+		command(proxy, args.get(0)).await;
+
+		Ok(())
+	}
+}
+
+/// The report is the number of dungeons ago, or a map name
+pub async fn command(proxy: &mut Proxy, report: Option<ArgValue<'_>>) {
 	let report_type: &str;
 	let report_id: usize;
 
-	match args.next() {
+	match report {
 		None => {
 			// no argument = generate live report
 			match generate_report::generate_report(&proxy.state.damage_monitor) {
@@ -67,8 +164,8 @@ pub async fn command(proxy: &mut Proxy, mut args: impl Iterator<Item = &str>) {
 			}
 		}
 		Some(arg) => {
-			let id = match arg.parse::<usize>() {
-				Ok(0) => {
+			let id = match arg {
+				ArgValue::Int(0) => {
 					// thats just the current dungeon......
 					proxy
 						.send_client(create_notification(
@@ -78,15 +175,23 @@ pub async fn command(proxy: &mut Proxy, mut args: impl Iterator<Item = &str>) {
 						.await;
 					return;
 				}
-				Ok(offset) => proxy
+				ArgValue::Int(offset) => proxy
 					.rotmguard
 					.damage_monitor_http
-					.find_memory_by_offset(offset),
-				Err(_) => proxy.rotmguard.damage_monitor_http.find_memory_by_name(arg),
+					.find_memory_by_offset(offset)
+					.map(|id| ("memory", id)),
+				ArgValue::Word(name) => {
+					let http = &proxy.rotmguard.damage_monitor_http;
+					// older ones may still be on disk
+					match http.find_memory_by_name(name) {
+						Some(id) => Some(("memory", id)),
+						None => http.find_archived_by_name(name).map(|id| ("archive", id)),
+					}
+				}
+				ArgValue::Threshold(_) => unreachable!("not one of the kinds of /dmg"),
 			};
 
-			report_type = "memory";
-			report_id = match id {
+			(report_type, report_id) = match id {
 				Some(x) => x,
 				None => {
 					proxy
@@ -118,6 +223,24 @@ pub async fn command(proxy: &mut Proxy, mut args: impl Iterator<Item = &str>) {
 		.await;
 }
 
+/// Sends the current damage to the live page, if anyone is watching
+pub fn live_update(proxy: &mut Proxy) {
+	let now = proxy.state.common.time;
+	let this = &mut proxy.state.damage_monitor;
+	if now.wrapping_sub(this.last_live_update) < LIVE_INTERVAL_MS {
+		return;
+	}
+	this.last_live_update = now;
+
+	let http = &proxy.rotmguard.damage_monitor_http;
+	if !http.live_subscribed() {
+		return;
+	}
+	if let Some(update) = generate_report::live_update(this) {
+		http.publish_live(&update);
+	}
+}
+
 pub fn set_map_name(proxy: &mut Proxy, name: &str) {
 	proxy.state.damage_monitor.map_name = Some(name.to_owned());
 }
@@ -164,6 +287,33 @@ pub fn remove_object(proxy: &mut Proxy, obj_id: u32) {
 	}
 }
 
+/// Notes when an enemy becomes invulnerable, which is how bosses usually end a phase.
+/// To be called before common sees the new stats, since they're compared with the old ones
+pub fn enemy_conditions(proxy: &mut Proxy, status: &ObjectStatus) {
+	let Some(object) = proxy.state.common.objects.get(status.object_id) else {
+		return;
+	};
+	let was_invulnerable = object.stats.conditions & CONDITION_BITFLAG::INVULNERABLE != 0;
+	// only the ones that were hit already, a phase can't end before it started
+	let Some(enemy) = proxy
+		.state
+		.damage_monitor
+		.enemies
+		.get_mut(&object.unique_id)
+	else {
+		return;
+	};
+
+	for stat in &status.stats {
+		if let (STAT_TYPE::CONDITION, Either::Right(conditions)) = (stat.stat_type, stat.data)
+			&& conditions as u64 & CONDITION_BITFLAG::INVULNERABLE != 0
+			&& !was_invulnerable
+		{
+			enemy.invulnerable_at.push(proxy.state.common.time);
+		}
+	}
+}
+
 pub fn death_notification(proxy: &mut Proxy, json: &str) {
 	#[derive(Deserialize)]
 	struct DeathNotification {
@@ -208,7 +358,13 @@ pub fn death_notification(proxy: &mut Proxy, json: &str) {
 	}
 }
 
-pub fn do_damage(proxy: &mut Proxy, target_obj_id: u32, damage_amount: u16, owner_id: u32) {
+pub fn do_damage(
+	proxy: &mut Proxy,
+	target_obj_id: u32,
+	damage_amount: u16,
+	owner_id: u32,
+	source: Option<Source>,
+) {
 	let target = match proxy.state.common.objects.get(target_obj_id) {
 		Some(x) => x,
 		None => return, // just accept that the server sends nonsensical data all the time...
@@ -225,13 +381,26 @@ pub fn do_damage(proxy: &mut Proxy, target_obj_id: u32, damage_amount: u16, owne
 
 	let shooter_id = shooter.unique_id;
 	let target_id = target.unique_id;
+	let now = proxy.state.common.time;
 
 	get_player(proxy, shooter_id);
-	get_enemy(proxy, target_id)
-		.player_damage
-		.entry(shooter_id)
-		.or_default()
-		.1 += damage_amount as i64;
+	let enemy = get_enemy(proxy, target_id);
+	enemy.player_damage.entry(shooter_id).or_default().1 += damage_amount as i64;
+
+	let samples = enemy.samples.entry(shooter_id).or_default();
+	match samples.last_mut() {
+		Some((time, damage)) if *time == now => *damage += damage_amount as i64,
+		_ => samples.push((now, damage_amount as i64)),
+	}
+
+	if let Some(source) = source {
+		*enemy
+			.sources
+			.entry(shooter_id)
+			.or_default()
+			.entry(source)
+			.or_default() += damage_amount as i64;
+	}
 }
 
 pub async fn enemyhit(proxy: &mut Proxy, bullet_id: u16, shooter_id: u32, target_id: u32) {
@@ -313,7 +482,26 @@ pub async fn enemyhit(proxy: &mut Proxy, bullet_id: u16, shooter_id: u32, target
 		total_damage -= total_damage / 10; // x 0.9
 	}
 
-	do_damage(proxy, target_id, total_damage, my_id);
+	let source = damage_source(proxy, &bullet, shooter_id);
+	do_damage(proxy, target_id, total_damage, my_id, Some(source));
+}
+
+// what our own bullet was shot with
+fn damage_source(proxy: &mut Proxy, bullet: &Bullet, shooter_id: u32) -> Source {
+	let my_id = proxy.state.common.objects.self_id;
+	let ability = proxy.state.common.objects.get_self().equipped_items[1]
+		.as_ref()
+		.map(|item| item.id);
+
+	match bullet.summoner_id {
+		// shot by something we summoned
+		Some(_) if shooter_id != my_id => Source::Summon(bullet.object_type),
+		// shot by the server for us, the bullet type is the class then
+		Some(_) => Source::Ability(ability.unwrap_or(bullet.object_type)),
+		None if ability == Some(bullet.object_type) => Source::Ability(bullet.object_type),
+		// the weapon, or the one before swapping
+		None => Source::Weapon(bullet.object_type),
+	}
 }
 
 impl DamageMonitor {
@@ -323,6 +511,7 @@ impl DamageMonitor {
 			map_name: Default::default(),
 			players: Default::default(),
 			enemies: Default::default(),
+			last_live_update: 0,
 		}
 	}
 	// checks whether a player has done any damage to any recorded enemy
@@ -413,6 +602,9 @@ fn get_enemy(proxy: &mut Proxy, unique_id: u64) -> &mut Enemy {
 				},
 				object_type: obj.type_id as u32,
 				player_damage: Default::default(),
+				samples: Default::default(),
+				sources: Default::default(),
+				invulnerable_at: Vec::new(),
 			}
 		})
 }
@@ -442,3 +634,123 @@ impl Drop for DamageMonitor {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		protocol::StatData,
+		proxy::{
+			logic::common::objects::Object,
+			replay::{Replay, test_rotmguard},
+		},
+	};
+
+	#[tokio::test]
+	async fn sources() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+		let proxy = replay.proxy();
+		proxy.state.common.objects.self_id = 1;
+		proxy.state.common.objects.get_self().equipped_items[1] = Some(Item {
+			id: 20,
+			enchantments: Default::default(),
+		});
+
+		let bullet = |object_type, summoner_id| Bullet {
+			damage: 100,
+			summoner_id,
+			object_type,
+			bullet_type: 0,
+			shot: None,
+		};
+		let sources = [
+			damage_source(proxy, &bullet(10, None), 1),
+			damage_source(proxy, &bullet(20, None), 1),
+			// a spell, shot by the server as the player
+			damage_source(proxy, &bullet(782, Some(1)), 1),
+			damage_source(proxy, &bullet(30, Some(1)), 2),
+		];
+		assert_eq!(
+			sources,
+			[
+				Source::Weapon(10),
+				Source::Ability(20),
+				Source::Ability(20),
+				Source::Summon(30)
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn phase_ends() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+		let proxy = replay.proxy();
+		let objects = &mut proxy.state.common.objects.objects;
+		objects.insert(
+			1,
+			Object {
+				unique_id: 10,
+				is_player: true,
+				..Default::default()
+			},
+		);
+		objects.insert(
+			2,
+			Object {
+				unique_id: 20,
+				..Default::default()
+			},
+		);
+
+		let invulnerable = |invulnerable| ObjectStatus {
+			object_id: 2,
+			pos_x: 0.0,
+			pos_y: 0.0,
+			stats: vec![StatData {
+				stat_type: STAT_TYPE::CONDITION,
+				data: Either::Right(if invulnerable {
+					CONDITION_BITFLAG::INVULNERABLE as i64
+				} else {
+					0
+				}),
+				secondary: 0,
+			}],
+		};
+		// what common does after the damage monitor has seen the stats
+		let set_invulnerable = |proxy: &mut Proxy, invulnerable: bool| {
+			proxy
+				.state
+				.common
+				.objects
+				.objects
+				.get_mut(&2)
+				.unwrap()
+				.stats
+				.conditions = if invulnerable {
+				CONDITION_BITFLAG::INVULNERABLE
+			} else {
+				0
+			};
+		};
+
+		// not hit yet, nothing to end
+		enemy_conditions(proxy, &invulnerable(true));
+		set_invulnerable(proxy, true);
+		enemy_conditions(proxy, &invulnerable(false));
+		set_invulnerable(proxy, false);
+
+		proxy.state.common.time = 1000;
+		do_damage(proxy, 2, 100, 1, None);
+		proxy.state.common.time = 2000;
+		enemy_conditions(proxy, &invulnerable(true));
+		set_invulnerable(proxy, true);
+		// still invulnerable
+		proxy.state.common.time = 2200;
+		enemy_conditions(proxy, &invulnerable(true));
+
+		assert_eq!(
+			proxy.state.damage_monitor.enemies[&20].invulnerable_at,
+			[2000]
+		);
+	}
+}
@@ -1,5 +1,7 @@
-use super::{DamageMonitor, TAKEN_DAMAGE_CRITERIA};
-use crate::damage_monitor_http_server::{EnemyTab, PlayerItem, PlayerRow, Report};
+use super::{DamageMonitor, Source, TAKEN_DAMAGE_CRITERIA, get_obj_type_name};
+use crate::damage_monitor_http_server::{
+	BURST_SECONDS, DamageSource, EnemyTab, LiveUpdate, PlayerItem, PlayerRow, Report,
+};
 use base64::{Engine, prelude::BASE64_STANDARD};
 use std::collections::BTreeMap;
 
@@ -10,6 +12,65 @@ pub fn generate_report(this: &DamageMonitor) -> Option<Report> {
 		None => return None,
 	};
 
+	let enemy_tabs = enemy_tabs(this);
+
+	let mut all_items: BTreeMap<u32, Option<String>> = this
+		.players
+		.iter()
+		.map(|(_, p)| {
+			p.items
+				.iter()
+				.filter_map(|item| item.as_ref().map(|item| item.id))
+		})
+		.flatten()
+		// and the sprites of the damage sources
+		.chain(
+			enemy_tabs
+				.iter()
+				.flat_map(|tab| &tab.players)
+				.flat_map(|player| &player.sources)
+				.map(|source| source.object_type),
+		)
+		.map(|item_id| (item_id, None))
+		.collect();
+	all_items.iter_mut().for_each(|(item_id, sprite)| {
+		*sprite = this
+			.rotmguard
+			.assets
+			.try_get_obj_sprite(*item_id)
+			.map(|raw_sprite| BASE64_STANDARD.encode(raw_sprite))
+	});
+
+	let mut all_enemies: BTreeMap<u32, Option<String>> = enemy_tabs
+		.iter()
+		.map(|enemy| (enemy.object_id, None))
+		.collect();
+	all_enemies.iter_mut().for_each(|(enemy_id, sprite)| {
+		*sprite = this
+			.rotmguard
+			.assets
+			.try_get_obj_sprite(*enemy_id)
+			.map(|raw_sprite| BASE64_STANDARD.encode(raw_sprite))
+	});
+
+	Some(Report {
+		map_name,
+		time: format!("{}", chrono::Local::now().format("%F %T")),
+		enemy_tabs,
+		all_items,
+		all_enemies,
+	})
+}
+
+/// generates the live update if map name set
+pub fn live_update(this: &DamageMonitor) -> Option<LiveUpdate> {
+	Some(LiveUpdate {
+		map_name: this.map_name.clone()?,
+		enemy_tabs: enemy_tabs(this),
+	})
+}
+
+fn enemy_tabs(this: &DamageMonitor) -> Vec<EnemyTab> {
 	// sort enemies by total damage done to them
 	let mut enemy_tabs: Vec<EnemyTab> = this
 		.enemies
@@ -17,6 +78,11 @@ pub fn generate_report(this: &DamageMonitor) -> Option<Report> {
 		.map(|(_enemy_id, enemy)| {
 			let total_damage = enemy.player_damage.values().map(|(_status, dmg)| dmg).sum();
 
+			let all_samples = || enemy.samples.values().flatten().map(|&(time, _)| time);
+			let start = all_samples().min().unwrap_or(0);
+			let end = all_samples().max().unwrap_or(0);
+			let seconds = (end - start) as usize / 1000 + 1;
+
 			EnemyTab {
 				name: enemy.name.clone(),
 				object_id: enemy.object_type,
@@ -28,6 +94,14 @@ pub fn generate_report(this: &DamageMonitor) -> Option<Report> {
 						.filter(|(_p, (_status, dmg))| *dmg > 0)
 						.map(|(&player_id, &(status, damage))| {
 							let player = &this.players[&player_id];
+							let samples = enemy.samples.get(&player_id).map(Vec::as_slice);
+							let dps = dps(samples.unwrap_or_default(), start, seconds);
+							let (burst_start, burst_dps) = burst(&dps);
+							let sources = enemy
+								.sources
+								.get(&player_id)
+								.map(|sources| damage_sources(this, sources, damage))
+								.unwrap_or_default();
 
 							PlayerRow {
 								name: player.name.clone(),
@@ -68,6 +142,10 @@ pub fn generate_report(this: &DamageMonitor) -> Option<Report> {
 											.collect(),
 									})
 								}),
+								dps,
+								burst_dps,
+								burst_start,
+								sources,
 							}
 						})
 						.collect();
@@ -76,6 +154,8 @@ pub fn generate_report(this: &DamageMonitor) -> Option<Report> {
 
 					player_rows
 				},
+				fight_duration: end - start,
+				phases: phases(all_samples(), &enemy.invulnerable_at),
 			}
 		})
 		.filter(|e| e.total_damage > TAKEN_DAMAGE_CRITERIA)
@@ -83,42 +163,106 @@ pub fn generate_report(this: &DamageMonitor) -> Option<Report> {
 
 	enemy_tabs.sort_by_key(|e| -e.total_damage); // negative to make it descending
 
-	let mut all_items: BTreeMap<u32, Option<String>> = this
-		.players
+	enemy_tabs
+}
+
+fn damage_sources(
+	this: &DamageMonitor,
+	sources: &BTreeMap<Source, i64>,
+	player_damage: i64,
+) -> Vec<DamageSource> {
+	let mut sources: Vec<DamageSource> = sources
 		.iter()
-		.map(|(_, p)| {
-			p.items
-				.iter()
-				.filter_map(|item| item.as_ref().map(|item| item.id))
+		.map(|(&source, &damage)| {
+			let (kind, object_type) = match source {
+				Source::Weapon(id) => ("Weapon", id),
+				Source::Ability(id) => ("Ability", id),
+				Source::Summon(object_type) => ("Summon", object_type),
+			};
+
+			DamageSource {
+				kind: kind.to_owned(),
+				object_type,
+				name: get_obj_type_name(&this.rotmguard, object_type).to_owned(),
+				damage,
+				damage_percent: format!("{:.2}", 100.0 * damage as f64 / player_damage as f64),
+			}
 		})
-		.flatten()
-		.map(|item_id| (item_id, None))
 		.collect();
-	all_items.iter_mut().for_each(|(item_id, sprite)| {
-		*sprite = this
-			.rotmguard
-			.assets
-			.try_get_obj_sprite(*item_id)
-			.map(|raw_sprite| BASE64_STANDARD.encode(raw_sprite))
-	});
 
-	let mut all_enemies: BTreeMap<u32, Option<String>> = enemy_tabs
-		.iter()
-		.map(|enemy| (enemy.object_id, None))
-		.collect();
-	all_enemies.iter_mut().for_each(|(enemy_id, sprite)| {
-		*sprite = this
-			.rotmguard
-			.assets
-			.try_get_obj_sprite(*enemy_id)
-			.map(|raw_sprite| BASE64_STANDARD.encode(raw_sprite))
-	});
+	sources.sort_by_key(|s| -s.damage); // negative to make it descending
 
-	Some(Report {
-		map_name,
-		time: format!("{}", chrono::Local::now().format("%F %T")),
-		enemy_tabs,
-		all_items,
-		all_enemies,
-	})
+	sources
+}
+
+// how long each phase lasted, from its first damage to its last.
+// A phase ends at each of the times given, phases without any damage are left out
+fn phases(times: impl Iterator<Item = u32>, ends: &[u32]) -> Vec<u32> {
+	// (first, last) damage of each phase
+	let mut phases = vec![None; ends.len() + 1];
+	for time in times {
+		let phase = ends.partition_point(|&end| end < time);
+		let (first, last) = phases[phase].get_or_insert((time, time));
+		*first = time.min(*first);
+		*last = time.max(*last);
+	}
+
+	phases
+		.into_iter()
+		.flatten()
+		.map(|(first, last)| last - first)
+		.collect()
+}
+
+// sums up the damage in each second since the start
+fn dps(samples: &[(u32, i64)], start: u32, seconds: usize) -> Vec<i64> {
+	let mut dps = vec![0; seconds];
+	for &(time, damage) in samples {
+		dps[(time - start) as usize / 1000] += damage;
+	}
+
+	dps
+}
+
+// (start second, average dps) of the window with the most damage
+fn burst(dps: &[i64]) -> (usize, i64) {
+	let window = BURST_SECONDS.min(dps.len());
+	if window == 0 {
+		return (0, 0);
+	}
+
+	dps.windows(window)
+		.map(|w| w.iter().sum::<i64>() / window as i64)
+		.enumerate()
+		// the first one if there are a few with the same damage
+		.rev()
+		.max_by_key(|&(_start, dps)| dps)
+		.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dps_and_burst() {
+		let samples = [(1000, 100), (1200, 50), (2999, 10), (7500, 400)];
+		let dps = dps(&samples, 1000, 7);
+		assert_eq!(dps, [150, 10, 0, 0, 0, 0, 400]);
+
+		assert_eq!(burst(&dps), (2, 80));
+		assert_eq!(burst(&[30, 60]), (0, 45));
+		assert_eq!(burst(&[]), (0, 0));
+	}
+
+	#[test]
+	fn phase_durations() {
+		let times = [1000, 2500, 4000, 9000, 9500, 15000];
+		assert_eq!(phases(times.into_iter(), &[]), [14000]);
+		// invulnerable after the hit at 4000, and twice between 9500 and 15000
+		assert_eq!(
+			phases(times.into_iter(), &[4000, 12000, 13000]),
+			[3000, 500, 0]
+		);
+	}
 }
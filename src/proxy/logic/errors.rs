@@ -0,0 +1,169 @@
+//! What to do when something goes wrong while handling a packet.
+//!
+//! Errors never kill the connection. If a packet can't be parsed it's forwarded as it was received,
+//! and if a feature fails, the packet is also forwarded as it was received (without any feature's
+//! changes) and depending on its policy the feature is either just logged or disabled for the rest
+//! of the connection, while everything else keeps working.
+
+use crate::{
+	Direction,
+	proxy::Proxy,
+	util::{RED, create_notification, packet_name},
+};
+use anyhow::Error;
+use std::collections::BTreeMap;
+use tracing::{error, warn};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum FeatureId {
+	DamageMonitor,
+	Common,
+	Profiles,
+	General,
+	AntiDebuffs,
+	FakeSlow,
+	AntiPush,
+	Autonexus,
+	AntiLag,
+	Con,
+	Notify,
+}
+
+pub enum Policy {
+	/// Log and keep going
+	Ignore,
+	/// Disable the feature for the rest of the connection after this many errors
+	Disable { after: u32 },
+}
+
+impl FeatureId {
+	pub fn name(self) -> &'static str {
+		match self {
+			FeatureId::DamageMonitor => "damage monitor",
+			FeatureId::Common => "object tracking",
+			FeatureId::Profiles => "profiles",
+			FeatureId::General => "general",
+			FeatureId::AntiDebuffs => "antidebuffs",
+			FeatureId::FakeSlow => "fakeslow",
+			FeatureId::AntiPush => "antipush",
+			FeatureId::Autonexus => "autonexus",
+			FeatureId::AntiLag => "antilag",
+			FeatureId::Con => "con",
+			FeatureId::Notify => "notify",
+		}
+	}
+	pub fn policy(self) -> Policy {
+		match self {
+			// occasional desyncs are expected, but if it keeps failing, the simulated HP can't be trusted
+			FeatureId::Autonexus => Policy::Disable { after: 5 },
+			_ => Policy::Ignore,
+		}
+	}
+}
+
+#[derive(Default)]
+pub struct Errors {
+	features: BTreeMap<FeatureId, u32>,
+	disabled: Vec<FeatureId>,
+	// by packet name
+	packets: BTreeMap<String, u32>,
+}
+
+/// Runs a fallible feature call, unless the feature was disabled.
+/// An error is handled according to the feature's policy instead of being returned.
+/// With `verdict:`, a failed call's block is undone and the verdict is marked as failed,
+/// so the packet is forwarded as it was received.
+macro_rules! run_feature {
+	($proxy:expr, $feature:expr, $call:expr $(,)?) => {
+		if $crate::proxy::logic::errors::is_enabled($proxy, $feature) {
+			if let Err(e) = $call {
+				$crate::proxy::logic::errors::feature_error($proxy, $feature, e).await;
+			}
+		}
+	};
+	($proxy:expr, $feature:expr, $call:expr, verdict: $verdict:ident $(,)?) => {
+		if $crate::proxy::logic::errors::is_enabled($proxy, $feature) {
+			let before = *$verdict;
+			if let Err(e) = $call {
+				*$verdict = $crate::proxy::logic::feature::Verdict {
+					failed: true,
+					..before
+				};
+				$crate::proxy::logic::errors::feature_error($proxy, $feature, e).await;
+			}
+		}
+	};
+}
+pub(crate) use run_feature;
+
+pub fn is_enabled(proxy: &Proxy, feature: FeatureId) -> bool {
+	!proxy.state.errors.disabled.contains(&feature)
+}
+
+pub async fn feature_error(proxy: &mut Proxy, feature: FeatureId, error: Error) {
+	proxy.rotmguard.stats.add_error();
+
+	let count = proxy.state.errors.features.entry(feature).or_default();
+	*count += 1;
+	let count = *count;
+
+	error!("{} error: {error:?}", feature.name());
+
+	match feature.policy() {
+		Policy::Ignore => {}
+		Policy::Disable { after } => {
+			if count < after {
+				return;
+			}
+
+			warn!("Disabling {} after {count} errors", feature.name());
+			proxy.state.errors.disabled.push(feature);
+
+			let notification = create_notification(
+				&format!(
+					"{} disabled for this connection after {count} errors. Check the logs, maybe your assets are outdated?",
+					feature.name()
+				),
+				RED,
+			);
+			proxy.send_client(notification).await;
+		}
+	}
+}
+
+/// The packet is to be forwarded as it was received
+pub fn packet_error(proxy: &mut Proxy, direction: Direction, packet_id: u8, error: Error) {
+	proxy.rotmguard.stats.add_error();
+
+	let name = packet_name(direction, packet_id).unwrap_or("UNKNOWN");
+	*proxy
+		.state
+		.errors
+		.packets
+		.entry(format!("{direction:?} {name}"))
+		.or_default() += 1;
+
+	error!("Error handling [{packet_id}] {name} packet, forwarding unchanged: {error:?}");
+}
+
+impl Drop for Errors {
+	fn drop(&mut self) {
+		if self.features.is_empty() && self.packets.is_empty() {
+			return;
+		}
+
+		let features = self
+			.features
+			.iter()
+			.map(|(feature, count)| format!("{}: {count}", feature.name()));
+		let packets = self
+			.packets
+			.iter()
+			.map(|(packet, count)| format!("{packet}: {count}"));
+
+		warn!(
+			"Errors during this connection: {}",
+			features.chain(packets).collect::<Vec<_>>().join(", ")
+		);
+	}
+}
@@ -1,13 +1,18 @@
-use super::antidebuffs;
+use super::{
+	antidebuffs,
+	commands::{Args, Command, Gate},
+	errors::FeatureId,
+	feature::{Feature, Verdict, self_condition_stats},
+	general, profiles,
+};
 use crate::{
-	proxy::{
-		Proxy,
-		logic::packets::{ExtraObject, StatData},
-	},
+	Rotmguard,
+	protocol::{ObjectStatus, S2CPacket, StatData},
+	proxy::Proxy,
 	util::{CONDITION_BITFLAG, GREEN, RED, STAT_TYPE, static_notification},
 };
+use anyhow::Result;
 use either::Either;
-use std::iter::once;
 
 pub struct FakeSlow {
 	condition: u64,
@@ -15,14 +20,54 @@ pub struct FakeSlow {
 	synced: bool,
 }
 
-impl Default for FakeSlow {
-	fn default() -> Self {
+impl FakeSlow {
+	pub fn new(rotmguard: &Rotmguard) -> Self {
 		Self {
 			condition: 0,
-			enabled: false,
+			enabled: *rotmguard.config.settings.fakeslow.lock().unwrap(),
 			synced: true,
 		}
 	}
+	/// Turns it on or off without notifying, for example when a profile overrides it
+	pub fn set(&mut self, enabled: bool) {
+		if self.enabled != enabled {
+			self.enabled = enabled;
+			self.synced = false;
+		}
+	}
+}
+
+impl Feature for FakeSlow {
+	const ID: FeatureId = FeatureId::FakeSlow;
+	const COMMANDS: &'static [Command] = &[Command {
+		name: "slow",
+		aliases: &[],
+		args: &[],
+		description: "Toggles fake slow",
+		gate: Gate::Always,
+	}];
+
+	async fn on_s2c(
+		proxy: &mut Proxy,
+		packet: &mut S2CPacket<'_>,
+		verdict: &mut Verdict,
+	) -> Result<()> {
+		for stat in self_condition_stats(proxy.state.common.objects.self_id, packet) {
+			let original = *stat;
+			self_condition_stat(proxy, stat);
+			verdict.modified |= *stat != original;
+		}
+
+		Ok(())
+	}
+	async fn on_command(proxy: &mut Proxy, _: &Command, _: &Args<'_>) -> Result<()> {
+		toggle(proxy).await;
+
+		Ok(())
+	}
+	fn extra_object_status(proxy: &mut Proxy) -> Option<ObjectStatus<'static>> {
+		extra_object_status(proxy)
+	}
 }
 
 /// To be called in NewTick when the condition stat about self is read
@@ -40,6 +85,7 @@ pub fn self_condition_stat(proxy: &mut Proxy, stat: &mut i64) {
 pub async fn toggle(proxy: &mut Proxy) {
 	proxy.state.fakeslow.enabled = !proxy.state.fakeslow.enabled;
 	proxy.state.fakeslow.synced = false;
+	profiles::set_fakeslow(proxy, proxy.state.fakeslow.enabled);
 
 	let notification = if proxy.state.fakeslow.enabled {
 		static_notification!("Fake slow enabled", GREEN)
@@ -48,15 +94,15 @@ pub async fn toggle(proxy: &mut Proxy) {
 	};
 
 	proxy.send_client(notification).await;
+
+	general::setting_changed(proxy).await;
 }
 
 // checks if fakeslow is not synced, and if so, returns an extra self object status
 // to add to a newtick packet.
-pub fn extra_object_status(
-	proxy: &mut Proxy,
-) -> impl Iterator<Item = ExtraObject<impl Iterator<Item = StatData<'_>> + ExactSizeIterator>> {
+pub fn extra_object_status(proxy: &mut Proxy) -> Option<ObjectStatus<'static>> {
 	if proxy.state.fakeslow.synced {
-		return None.into_iter();
+		return None;
 	}
 	proxy.state.fakeslow.synced = true;
 
@@ -66,15 +112,41 @@ pub fn extra_object_status(
 		new_condition |= CONDITION_BITFLAG::SLOW as i64;
 	}
 
-	Some(ExtraObject {
-		obj_id: proxy.state.common.objects.self_id,
+	Some(ObjectStatus {
+		object_id: proxy.state.common.objects.self_id,
 		pos_x: 0.0, // literally doesnt matter
 		pos_y: 0.0,
-		stats: once(StatData {
+		stats: vec![StatData {
 			stat_type: STAT_TYPE::CONDITION,
 			data: Either::Right(new_condition),
 			secondary: -1,
-		}),
+		}],
 	})
-	.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::proxy::replay::{Replay, test_rotmguard};
+
+	#[tokio::test]
+	async fn toggle_resyncs_condition() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+		let proxy = replay.proxy();
+		proxy.state.common.objects.self_id = 100;
+
+		assert!(FakeSlow::extra_object_status(proxy).is_none());
+
+		let command = &FakeSlow::COMMANDS[0];
+		let args = command.parse_args(&[]).unwrap();
+		FakeSlow::on_command(proxy, command, &args).await.unwrap();
+		let status = FakeSlow::extra_object_status(proxy).unwrap();
+		assert_eq!(status.object_id, 100);
+		assert_eq!(
+			status.stats[0].data,
+			Either::Right(CONDITION_BITFLAG::SLOW as i64)
+		);
+		// only sent once
+		assert!(FakeSlow::extra_object_status(proxy).is_none());
+	}
 }
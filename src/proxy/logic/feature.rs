@@ -0,0 +1,226 @@
+//! The interface between the packet dispatcher and the features (cheats).
+//!
+//! Every packet that the proxy knows is decoded once and then shown to all features in [`registry!`]
+//! order. Features may modify or block the packet, and add their own object statuses or tiles to
+//! NewTick and Update packets. A failing hook is handled according to the feature's error policy,
+//! and the packet is forwarded as it was received.
+
+use super::{
+	antidebuffs::AntiDebuffs,
+	antilag::AntiLag,
+	antipush::AntiPush,
+	autonexus::Autonexus,
+	commands::{self, Args, Command},
+	common::Common,
+	con::Con,
+	damage_monitor::DamageMonitor,
+	errors::{self, FeatureId, run_feature},
+	fakeslow::FakeSlow,
+	general::General,
+	notify::Notify,
+	profiles::Profiles,
+};
+use crate::{
+	protocol::{C2SPacket, NewTick, ObjectStatus, PlayerText, S2CPacket, Tile},
+	proxy::Proxy,
+	util::STAT_TYPE,
+};
+use anyhow::Result;
+use either::Either;
+
+/// What happens with the packet after all features have seen it
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub struct Verdict {
+	/// Don't forward the packet
+	pub block: bool,
+	/// The packet was changed and has to be encoded again
+	pub modified: bool,
+	/// A feature failed, maybe halfway through changing the packet
+	pub failed: bool,
+}
+
+impl Verdict {
+	/// Whether to forward the changed packet instead of the bytes as they were received
+	pub fn encode(&self) -> bool {
+		self.modified && !self.failed
+	}
+}
+
+/// All hooks have empty default implementations, so a feature only implements what it needs.
+/// The state of the feature lives in [`State`](super::State).
+pub trait Feature {
+	const ID: FeatureId;
+	/// Chat commands of this feature
+	const COMMANDS: &'static [Command] = &[];
+
+	/// A packet from the client, before it is forwarded to the server
+	async fn on_c2s(
+		_proxy: &mut Proxy,
+		_packet: &mut C2SPacket<'_>,
+		_verdict: &mut Verdict,
+	) -> Result<()> {
+		Ok(())
+	}
+	/// A packet from the server, before it is forwarded to the client
+	async fn on_s2c(
+		_proxy: &mut Proxy,
+		_packet: &mut S2CPacket<'_>,
+		_verdict: &mut Verdict,
+	) -> Result<()> {
+		Ok(())
+	}
+	/// After all features have seen the NewTick packet
+	async fn on_tick(_proxy: &mut Proxy, _tick: &NewTick<'_>) -> Result<()> {
+		Ok(())
+	}
+	/// One of [`Feature::COMMANDS`] was used, with valid arguments
+	async fn on_command(_proxy: &mut Proxy, _command: &Command, _args: &Args<'_>) -> Result<()> {
+		Ok(())
+	}
+	/// An object status to add to the next NewTick packet
+	fn extra_object_status(_proxy: &mut Proxy) -> Option<ObjectStatus<'static>> {
+		None
+	}
+	/// Tiles to add to the next Update packet
+	fn extra_tiles(_proxy: &mut Proxy) -> Vec<Tile> {
+		Vec::new()
+	}
+}
+
+/// Generates functions which call a hook of every feature in order
+macro_rules! registry {
+	($($feature:ty),* $(,)?) => {
+		async fn all_on_c2s(proxy: &mut Proxy, packet: &mut C2SPacket<'_>, verdict: &mut Verdict) {
+			$(run_feature!(proxy, <$feature>::ID, <$feature>::on_c2s(proxy, packet, verdict).await, verdict: verdict);)*
+		}
+		async fn all_on_s2c(proxy: &mut Proxy, packet: &mut S2CPacket<'_>, verdict: &mut Verdict) {
+			$(run_feature!(proxy, <$feature>::ID, <$feature>::on_s2c(proxy, packet, verdict).await, verdict: verdict);)*
+		}
+		async fn all_on_tick(proxy: &mut Proxy, tick: &NewTick<'_>) {
+			$(run_feature!(proxy, <$feature>::ID, <$feature>::on_tick(proxy, tick).await);)*
+		}
+		/// All commands, in registry order
+		pub fn commands() -> Vec<(FeatureId, &'static Command)> {
+			let mut commands = Vec::new();
+			$(commands.extend(<$feature>::COMMANDS.iter().map(|command| (<$feature>::ID, command)));)*
+
+			commands
+		}
+		pub async fn run_command(proxy: &mut Proxy, feature: FeatureId, command: &Command, args: &Args<'_>) {
+			$(
+				if feature == <$feature>::ID {
+					run_feature!(proxy, feature, <$feature>::on_command(proxy, command, args).await);
+				}
+			)*
+		}
+		fn all_extra_object_statuses(proxy: &mut Proxy) -> Vec<ObjectStatus<'static>> {
+			let mut statuses = Vec::new();
+			$(
+				if errors::is_enabled(proxy, <$feature>::ID) {
+					statuses.extend(<$feature>::extra_object_status(proxy));
+				}
+			)*
+
+			statuses
+		}
+		fn all_extra_tiles(proxy: &mut Proxy) -> Vec<Tile> {
+			let mut tiles = Vec::new();
+			$(
+				if errors::is_enabled(proxy, <$feature>::ID) {
+					tiles.extend(<$feature>::extra_tiles(proxy));
+				}
+			)*
+
+			tiles
+		}
+	};
+}
+
+// The order matters (and is also the order of /help):
+// - the damage monitor must see removed objects before they are forgotten by common,
+//   and new conditions before common replaces the old ones
+// - common must track objects and stats before the other features use them
+// - profiles must be picked before the features that read the overridable settings
+// - antidebuffs must go before fakeslow, which remembers the condition stat it's given
+registry!(
+	General,
+	DamageMonitor,
+	Common,
+	Profiles,
+	AntiDebuffs,
+	FakeSlow,
+	AntiPush,
+	Autonexus,
+	AntiLag,
+	Con,
+	Notify,
+);
+
+pub async fn on_c2s(proxy: &mut Proxy, packet: &mut C2SPacket<'_>) -> Verdict {
+	let mut verdict = Verdict::default();
+
+	if let C2SPacket::PlayerText(PlayerText { text }) = packet {
+		verdict.block = commands::handle(proxy, text).await;
+	}
+
+	all_on_c2s(proxy, packet, &mut verdict).await;
+
+	verdict
+}
+
+pub async fn on_s2c(proxy: &mut Proxy, packet: &mut S2CPacket<'_>) -> Verdict {
+	let mut verdict = Verdict::default();
+
+	all_on_s2c(proxy, packet, &mut verdict).await;
+
+	// if a feature failed the changes are not forwarded, so the extras wait for the next packet
+	match packet {
+		S2CPacket::Update(update) if !verdict.failed => {
+			let extra = all_extra_tiles(proxy);
+			if !extra.is_empty() {
+				update.tiles.extend(extra);
+				verdict.modified = true;
+			}
+		}
+		S2CPacket::NewTick(tick) => {
+			if !verdict.failed {
+				let extra = all_extra_object_statuses(proxy);
+				if !extra.is_empty() {
+					tick.statuses.extend(extra);
+					verdict.modified = true;
+				}
+			}
+
+			all_on_tick(proxy, tick).await;
+		}
+		_ => {}
+	}
+
+	verdict
+}
+
+/// The condition stats of the player in an Update or NewTick packet, for features that modify them
+pub fn self_condition_stats<'p>(
+	self_id: u32,
+	packet: &'p mut S2CPacket<'_>,
+) -> impl Iterator<Item = &'p mut i64> {
+	let statuses: Vec<&mut ObjectStatus> = match packet {
+		S2CPacket::Update(update) => update
+			.new_objects
+			.iter_mut()
+			.map(|object| &mut object.status)
+			.collect(),
+		S2CPacket::NewTick(tick) => tick.statuses.iter_mut().collect(),
+		_ => Vec::new(),
+	};
+
+	statuses
+		.into_iter()
+		.filter(move |status| status.object_id == self_id)
+		.flat_map(|status| status.stats.iter_mut())
+		.filter(|stat| stat.stat_type == STAT_TYPE::CONDITION)
+		.filter_map(|stat| match &mut stat.data {
+			Either::Right(condition) => Some(condition),
+			Either::Left(_) => None,
+		})
+}
@@ -0,0 +1,147 @@
+//! Commands that don't belong to any particular feature
+
+use super::{
+	commands::{self, Arg, ArgKind, Args, Command, Gate},
+	errors::FeatureId,
+	feature::Feature,
+};
+use crate::{
+	config::CONFIG_PATH,
+	proxy::Proxy,
+	util::{BLUE, GREEN, RED, create_notification, static_notification},
+};
+use anyhow::Result;
+use tracing::{error, info, warn};
+
+pub struct General;
+
+impl Feature for General {
+	const ID: FeatureId = FeatureId::General;
+	const COMMANDS: &'static [Command] = &[
+		Command {
+			name: "help",
+			aliases: &[],
+			args: &[Arg {
+				name: "command|page",
+				kinds: &[ArgKind::Int, ArgKind::Word],
+				optional: true,
+			}],
+			description: "Lists the commands, or explains one",
+			gate: Gate::Always,
+		},
+		Command {
+			name: "hi",
+			aliases: &["rotmguard"],
+			args: &[],
+			description: "Checks if you're connected through rotmguard",
+			gate: Gate::Always,
+		},
+		Command {
+			name: "devmode",
+			aliases: &[],
+			args: &[],
+			description: "Toggles developer mode",
+			gate: Gate::Always,
+		},
+		Command {
+			name: "save",
+			aliases: &[],
+			args: &[],
+			description: "Saves the current settings to rotmguard.toml",
+			gate: Gate::Always,
+		},
+		Command {
+			name: "reload",
+			aliases: &[],
+			args: &[],
+			description: "Applies changes made to rotmguard.toml",
+			gate: Gate::Always,
+		},
+	];
+
+	async fn on_command(proxy: &mut Proxy, command: &Command, args: &Args<'_>) -> Result<()> {
+		match command.name {
+			"help" => commands::help(proxy, args).await,
+			"hi" => {
+				let notification = static_notification!("hi :)", BLUE);
+				proxy.send_client(notification).await;
+			}
+			"devmode" => {
+				let state = {
+					let mut dev_mode = proxy.rotmguard.config.settings.dev_mode.lock().unwrap();
+					*dev_mode = !*dev_mode;
+					*dev_mode
+				};
+
+				let notification = if state {
+					static_notification!("developer mode on", GREEN)
+				} else {
+					static_notification!("developer mode off", RED)
+				};
+				proxy.send_client(notification).await;
+
+				setting_changed(proxy).await;
+			}
+			"save" => {
+				let saved = save(proxy).await;
+				if saved {
+					let notification = static_notification!("Settings saved", GREEN);
+					proxy.send_client(notification).await;
+				}
+			}
+			"reload" => {
+				let notification = match proxy.rotmguard.config.reload(CONFIG_PATH) {
+					Ok(changed) if changed.is_empty() => {
+						static_notification!("Reloaded, nothing changed", BLUE)
+					}
+					Ok(changed) => {
+						info!("Config reloaded, changed: {}", changed.join(", "));
+						let settings = &proxy.rotmguard.config.settings;
+						match settings.check_servers(&proxy.rotmguard.rotmg_servers) {
+							Ok(()) => create_notification(
+								&format!("Reloaded:\n{}", changed.join("\n")),
+								GREEN,
+							),
+							Err(e) => {
+								warn!("{e}");
+								create_notification(
+									&format!("Reloaded:\n{}\nBut {e}", changed.join("\n")),
+									RED,
+								)
+							}
+						}
+					}
+					Err(e) => {
+						error!("Error reloading config: {e:?}");
+						create_notification(&format!("Not reloaded: {e:#}"), RED)
+					}
+				};
+				proxy.send_client(notification).await;
+			}
+			_ => {}
+		}
+
+		Ok(())
+	}
+}
+
+/// To be called after a setting was changed with a command, saves it if autosave is enabled
+pub async fn setting_changed(proxy: &mut Proxy) {
+	if *proxy.rotmguard.config.settings.autosave.lock().unwrap() {
+		save(proxy).await;
+	}
+}
+
+// returns whether successful, the player is notified about errors
+async fn save(proxy: &mut Proxy) -> bool {
+	match proxy.rotmguard.config.save(CONFIG_PATH) {
+		Ok(()) => true,
+		Err(e) => {
+			error!("Error saving settings: {e:?}");
+			let notification = create_notification(&format!("Error saving settings: {e}"), RED);
+			proxy.send_client(notification).await;
+
+			false
+		}
+	}
+}
@@ -1,6 +1,14 @@
 //! Notification when connection over
 
-use crate::proxy::Proxy;
+use super::{
+	commands::{Args, Command, Gate},
+	errors::FeatureId,
+	feature::Feature,
+};
+use crate::{
+	proxy::Proxy,
+	util::{GREEN, static_notification},
+};
 use anyhow::{Context, Result};
 use std::io::Write;
 use tempfile::Builder;
@@ -11,11 +19,32 @@ pub struct Notify {
 	enabled: bool,
 }
 
+impl Feature for Notify {
+	const ID: FeatureId = FeatureId::Notify;
+	const COMMANDS: &'static [Command] = &[Command {
+		name: "notify",
+		aliases: &[],
+		args: &[],
+		description: "Shows a desktop notification when the connection ends",
+		gate: Gate::Always,
+	}];
+
+	async fn on_command(proxy: &mut Proxy, _: &Command, _: &Args<'_>) -> Result<()> {
+		enable(proxy);
+		proxy
+			.send_client(static_notification!("notification enabled", GREEN))
+			.await;
+
+		Ok(())
+	}
+}
+
 pub fn enable(proxy: &mut Proxy) {
 	proxy.state.notify.enabled = true;
 }
 
-fn show_notification() -> Result<()> {
+/// Shows a desktop notification with the rotmguard icon
+pub fn desktop_notification(summary: &str) -> Result<()> {
 	let icon = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/icon.png"));
 
 	let mut tempfile = Builder::new()
@@ -24,7 +53,7 @@ fn show_notification() -> Result<()> {
 		.context("creating tempfile")?;
 	tempfile.write_all(icon).context("writing tempfile")?;
 	notify_rust::Notification::new()
-		.summary("Rotmguard level change")
+		.summary(summary)
 		.icon(
 			tempfile
 				.path()
@@ -43,7 +72,7 @@ impl Drop for Notify {
 			return;
 		}
 
-		if let Err(e) = show_notification() {
+		if let Err(e) = desktop_notification("Rotmguard level change") {
 			error!("erorr displaying desktop notification: {e:?}");
 		}
 	}
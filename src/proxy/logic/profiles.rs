@@ -0,0 +1,215 @@
+//! Per-class and per-character overrides of some settings (see `config::Profile`).
+//!
+//! The profile is picked once the player's class and name are known from the self object.
+//! Features read the overridable settings through this module instead of the config directly.
+
+use super::{
+	errors::FeatureId,
+	feature::{Feature, Verdict},
+};
+use crate::{
+	config::{Debuffs, Profile, Threshold},
+	protocol::S2CPacket,
+	proxy::Proxy,
+	util::{BLUE, create_notification},
+};
+use anyhow::Result;
+use tracing::info;
+
+#[derive(Default)]
+pub struct Profiles {
+	player: Option<Player>,
+}
+
+struct Player {
+	class: String,
+	object_type: u16,
+	name: String,
+}
+
+impl Feature for Profiles {
+	const ID: FeatureId = FeatureId::Profiles;
+
+	async fn on_s2c(proxy: &mut Proxy, packet: &mut S2CPacket<'_>, _: &mut Verdict) -> Result<()> {
+		if !matches!(packet, S2CPacket::Update(_) | S2CPacket::NewTick(_)) {
+			return Ok(());
+		}
+		if proxy.state.profiles.player.is_some() {
+			return Ok(());
+		}
+
+		// the self object must have been seen with its name
+		let objects = &proxy.state.common.objects;
+		let Some(object) = objects.get(objects.self_id) else {
+			return Ok(());
+		};
+		let Some(name) = &object.name else {
+			return Ok(());
+		};
+		let class = match proxy.rotmguard.assets.objects.get(&(object.type_id as u32)) {
+			Some(object) => object.name.clone(),
+			None => String::new(),
+		};
+
+		proxy.state.profiles.player = Some(Player {
+			class,
+			object_type: object.type_id,
+			name: name.clone(),
+		});
+
+		let Some(profile) = with_active(proxy, |profile| Some(profile.clone())) else {
+			return Ok(());
+		};
+
+		let label = [profile.name.as_deref(), profile.class.as_deref()]
+			.into_iter()
+			.flatten()
+			.collect::<Vec<_>>()
+			.join(" ");
+		info!("Using profile {label}");
+		proxy
+			.send_client(create_notification(&format!("Using profile {label}"), BLUE))
+			.await;
+
+		if let Some(fakeslow) = profile.fakeslow {
+			proxy.state.fakeslow.set(fakeslow);
+		}
+
+		Ok(())
+	}
+}
+
+fn matches(profile: &Profile, player: &Player) -> bool {
+	let class = profile.class.as_ref().is_none_or(|class| {
+		class.eq_ignore_ascii_case(&player.class) || *class == player.object_type.to_string()
+	});
+	let name = profile
+		.name
+		.as_ref()
+		.is_none_or(|name| name.eq_ignore_ascii_case(&player.name));
+	let any = profile.class.is_some() || profile.name.is_some();
+
+	any && class && name
+}
+
+// index of the profile to use for the player
+fn find(profiles: &[Profile], player: &Player) -> Option<usize> {
+	let matching = profiles
+		.iter()
+		.enumerate()
+		.filter(|(_, profile)| matches(profile, player));
+
+	// one with a character name wins, otherwise the first one
+	let mut found = None;
+	for (i, profile) in matching {
+		if profile.name.is_some() {
+			return Some(i);
+		}
+		found.get_or_insert(i);
+	}
+
+	found
+}
+
+fn with_active<T>(proxy: &Proxy, f: impl FnOnce(&Profile) -> Option<T>) -> Option<T> {
+	let player = proxy.state.profiles.player.as_ref()?;
+	let profiles = proxy.rotmguard.config.settings.profiles.lock().unwrap();
+
+	f(&profiles[find(&profiles, player)?])
+}
+
+// returns false if there's no active profile or f returned false
+fn update_active(proxy: &Proxy, f: impl FnOnce(&mut Profile) -> bool) -> bool {
+	let Some(player) = proxy.state.profiles.player.as_ref() else {
+		return false;
+	};
+	let mut profiles = proxy.rotmguard.config.settings.profiles.lock().unwrap();
+
+	match find(&profiles, player) {
+		Some(i) => f(&mut profiles[i]),
+		None => false,
+	}
+}
+
+pub fn autonexus_hp(proxy: &Proxy) -> Threshold {
+	with_active(proxy, |p| p.autonexus_hp)
+		.unwrap_or_else(|| *proxy.rotmguard.config.settings.autonexus_hp.lock().unwrap())
+}
+
+pub fn antilag(proxy: &Proxy) -> bool {
+	with_active(proxy, |p| p.antilag)
+		.unwrap_or_else(|| *proxy.rotmguard.config.settings.antilag.lock().unwrap())
+}
+
+pub fn debuffs(proxy: &Proxy) -> Debuffs {
+	with_active(proxy, |p| p.debuffs)
+		.unwrap_or_else(|| *proxy.rotmguard.config.settings.debuffs.lock().unwrap())
+}
+
+// Setters change the active profile if it overrides the setting, otherwise the normal settings
+
+pub fn set_autonexus_hp(proxy: &Proxy, hp: Threshold) {
+	let in_profile = update_active(proxy, |p| {
+		p.autonexus_hp.as_mut().map(|v| *v = hp).is_some()
+	});
+	if !in_profile {
+		*proxy.rotmguard.config.settings.autonexus_hp.lock().unwrap() = hp;
+	}
+}
+
+pub fn set_antilag(proxy: &Proxy, antilag: bool) {
+	let in_profile = update_active(proxy, |p| {
+		p.antilag.as_mut().map(|v| *v = antilag).is_some()
+	});
+	if !in_profile {
+		*proxy.rotmguard.config.settings.antilag.lock().unwrap() = antilag;
+	}
+}
+
+pub fn set_fakeslow(proxy: &Proxy, fakeslow: bool) {
+	let in_profile = update_active(proxy, |p| {
+		p.fakeslow.as_mut().map(|v| *v = fakeslow).is_some()
+	});
+	if !in_profile {
+		*proxy.rotmguard.config.settings.fakeslow.lock().unwrap() = fakeslow;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn profile(class: Option<&str>, name: Option<&str>) -> Profile {
+		Profile {
+			class: class.map(str::to_owned),
+			name: name.map(str::to_owned),
+			autonexus_hp: None,
+			antilag: None,
+			fakeslow: None,
+			debuffs: None,
+		}
+	}
+
+	#[test]
+	fn pick() {
+		let player = Player {
+			class: "Knight".to_owned(),
+			object_type: 0x031e,
+			name: "Someone".to_owned(),
+		};
+
+		let profiles = [
+			profile(None, None),
+			profile(Some("wizard"), None),
+			profile(Some("knight"), None),
+			profile(Some("798"), Some("someone")),
+			profile(None, Some("Someone")),
+		];
+
+		assert_eq!(find(&profiles[..3], &player), Some(2));
+		// character name wins, class given as object type
+		assert_eq!(find(&profiles, &player), Some(3));
+		assert_eq!(find(&profiles[..2], &player), None);
+		assert!(!matches(&profile(Some("Knight"), Some("Other")), &player));
+	}
+}
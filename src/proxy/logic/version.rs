@@ -0,0 +1,84 @@
+//! Game build version check, so we dont act on misparsed packets after a game update
+
+use crate::{
+	proxy::Proxy,
+	util::{PACKET_ID, RED, create_notification},
+};
+use anyhow::Error;
+use tracing::warn;
+
+/// Build versions (as sent in MapInfo) that the packet layouts in `protocol` were written for.
+/// There's no capture of this version in the repository, so when in doubt (or after a game update)
+/// record one with packet logging and run `rotmguard inspect` on it: it shows the build version
+/// of each MapInfo, whether it's in this list, and any packets that don't decode cleanly.
+/// If this is wrong every connection is passed through, which the player is told about in game.
+pub const SUPPORTED_BUILD_VERSIONS: &[&str] = &["5.6.1.0.0"];
+
+#[derive(Default)]
+pub struct Version {
+	/// Whether all packets are being forwarded untouched, without any parsing
+	pub passthrough: bool,
+	/// Whether the player still needs to be told about passthrough mode
+	notice_pending: Option<String>,
+}
+
+fn is_supported(proxy: &Proxy, build_version: &str) -> bool {
+	SUPPORTED_BUILD_VERSIONS.contains(&build_version)
+		|| proxy
+			.rotmguard
+			.config
+			.settings
+			.extra_build_versions
+			.lock()
+			.unwrap()
+			.iter()
+			.any(|v| v == build_version)
+}
+
+/// To be called with the build version in MapInfo.
+/// Returns false if it's not supported, in which case passthrough mode is now on
+pub fn check(proxy: &mut Proxy, build_version: &str) -> bool {
+	if is_supported(proxy, build_version) {
+		return true;
+	}
+
+	warn!(
+		"Unsupported game build version {build_version:?}, passing all packets through untouched"
+	);
+	enter_passthrough(
+		proxy,
+		format!(
+			"Unsupported game version {build_version}. Rotmguard is disabled for this connection. \
+			If you're sure it's fine, add it to extra_build_versions in rotmguard.toml."
+		),
+	);
+
+	false
+}
+
+/// To be called if MapInfo itself could not be parsed
+pub fn unknown_layout(proxy: &mut Proxy, error: Error) {
+	warn!("Couldn't parse MapInfo, passing all packets through untouched: {error:?}");
+	enter_passthrough(
+		proxy,
+		"Game version could not be determined. Rotmguard is disabled for this connection."
+			.to_owned(),
+	);
+}
+
+fn enter_passthrough(proxy: &mut Proxy, notice: String) {
+	proxy.state.version.passthrough = true;
+	proxy.state.version.notice_pending = Some(notice);
+}
+
+/// To be called with every S2C packet in passthrough mode, after it's forwarded.
+/// Tells the player about passthrough mode once they are actually in game.
+pub async fn passthrough_s2c(proxy: &mut Proxy, packet_id: u8) {
+	if packet_id != PACKET_ID::S2C_CREATE_SUCCESS {
+		return;
+	}
+
+	if let Some(notice) = proxy.state.version.notice_pending.take() {
+		proxy.send_client(create_notification(&notice, RED)).await;
+	}
+}
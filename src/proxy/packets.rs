@@ -1,34 +0,0 @@
-mod aoe;
-mod aoeack;
-pub mod common;
-mod create_success;
-mod damage;
-mod enemyhit;
-mod enemyshoot;
-mod ground_damage;
-mod mapinfo;
-mod r#move;
-mod newtick;
-mod notification;
-mod playerhit;
-mod playershoot;
-mod playertext;
-mod serverplayershoot;
-mod update;
-
-pub use aoe::{AoeEffect, aoe};
-pub use aoeack::aoeack;
-pub use create_success::create_success;
-pub use damage::damage;
-pub use enemyhit::enemyhit;
-pub use enemyshoot::enemyshoot;
-pub use ground_damage::ground_damage;
-pub use mapinfo::mapinfo;
-pub use r#move::r#move;
-pub use newtick::{ExtraObject, StatData, newtick};
-pub use notification::notification;
-pub use playerhit::playerhit;
-pub use playershoot::playershoot;
-pub use playertext::playertext;
-pub use serverplayershoot::serverplayershoot;
-pub use update::update;
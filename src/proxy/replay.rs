@@ -0,0 +1,990 @@
+//! Offline replay of packet captures (see `packet_logger`) through the proxy logic.
+//!
+//! No game, server or iptables involved - the proxy just has its write channels
+//! connected to us, and everything that would have been sent to either side is recorded.
+
+use super::{Proxy, logic};
+use crate::{Direction, Rotmguard, packet_logger::CapturedPacket};
+use anyhow::Result;
+use bytes::{Bytes, BytesMut};
+use futures::stream::FuturesUnordered;
+use std::sync::Arc;
+use tokio::sync::mpsc::{Receiver, channel};
+
+pub struct Replay {
+	proxy: Proxy,
+	client: Receiver<Bytes>,
+	server: Receiver<Bytes>,
+	/// packets that would have been sent to the client
+	pub to_client: Vec<Bytes>,
+	/// packets that would have been sent to the server
+	pub to_server: Vec<Bytes>,
+}
+
+impl Replay {
+	pub fn new(rotmguard: Rotmguard) -> Result<Self> {
+		let rotmguard = Arc::new(rotmguard);
+
+		// nothing is draining these while a packet is handled, but a single packet
+		// never results in anywhere near WRITE_CHANNEL_SIZE packets sent
+		let (c_send, c_recv) = channel(super::WRITE_CHANNEL_SIZE);
+		let (s_send, s_recv) = channel(super::WRITE_CHANNEL_SIZE);
+
+		let state = logic::State::new(&rotmguard)?;
+
+		Ok(Self {
+			proxy: Proxy {
+				rotmguard,
+				client: c_send,
+				server: s_send,
+				writer_tasks: FuturesUnordered::new(),
+				state,
+			},
+			client: c_recv,
+			server: s_recv,
+			to_client: Vec::new(),
+			to_server: Vec::new(),
+		})
+	}
+	/// Handles a single packet as if it was just read from the given direction
+	pub async fn feed(&mut self, direction: Direction, packet: BytesMut) -> Result<()> {
+		let r = match direction {
+			Direction::C2S => logic::handle_c2s_packet(&mut self.proxy, packet).await,
+			Direction::S2C => logic::handle_s2c_packet(&mut self.proxy, packet).await,
+		};
+
+		while let Ok(packet) = self.client.try_recv() {
+			self.to_client.push(packet);
+		}
+		while let Ok(packet) = self.server.try_recv() {
+			self.to_server.push(packet);
+		}
+
+		r
+	}
+	/// Handles all packets of a capture in order. Stops at the first error, like the proxy would.
+	pub async fn capture(&mut self, packets: Vec<CapturedPacket>) -> Result<()> {
+		for packet in packets {
+			self.feed(packet.direction, packet.data).await?;
+		}
+
+		Ok(())
+	}
+	/// For driving features directly
+	pub fn proxy(&mut self) -> &mut Proxy {
+		&mut self.proxy
+	}
+	/// packets (by id) that would have been sent to the given side
+	pub fn sent_ids(&self, direction: Direction) -> Vec<u8> {
+		let sent = match direction {
+			Direction::C2S => &self.to_server,
+			Direction::S2C => &self.to_client,
+		};
+
+		sent.iter().map(|p| p[0]).collect()
+	}
+}
+
+// tiles and objects known to the test rotmguard
+pub const TILE_WEB: u16 = 1;
+pub const TILE_GRASS: u16 = 2;
+pub const TILE_CONVEYOR: u16 = 3;
+pub const OBJ_PLAYER: u16 = 0x300;
+pub const OBJ_ENEMY: u16 = 0x400;
+
+/// A minimal rotmguard for replays: everything disabled, no assets except
+/// a few test tiles and objects, no servers.
+pub fn test_rotmguard() -> Rotmguard {
+	use crate::{
+		assets::{Assets, Movement, Object, Path, ProjectileInfo, Tile},
+		config::Config,
+	};
+	use either::Either;
+
+	let config: Config = toml::from_str(
+		r#"
+		assets_res = ""
+
+		[settings]
+		autonexus_hp = 20
+		antilag = false
+		dev_mode = false
+
+		[settings.debuffs]
+
+		[settings.edit_assets]
+		enabled = false
+		force_debuffs = false
+
+		[settings.damage_monitor]
+		enabled = false
+		keep_memory = 1
+		open_browser = false
+		bind_address = "127.0.0.1"
+		bind_port = 0
+		"#,
+	)
+	.unwrap();
+
+	let mut assets = Assets::empty();
+	for (id, name, is_conveyor) in [
+		(TILE_WEB, "Spider Dirt Web", false),
+		(TILE_GRASS, "Grass", false),
+		(TILE_CONVEYOR, "Conveyor", true),
+	] {
+		assets.tiles.insert(
+			id as u32,
+			Tile {
+				name: name.to_owned(),
+				damage: None,
+				is_conveyor,
+			},
+		);
+	}
+	assets.objects.insert(
+		OBJ_PLAYER as u32,
+		Object {
+			name: "Wizard".to_owned(),
+			sprite: None,
+			projectiles: Default::default(),
+		},
+	);
+	assets.objects.insert(
+		OBJ_ENEMY as u32,
+		Object {
+			name: "Pirate".to_owned(),
+			sprite: None,
+			projectiles: [(
+				0,
+				ProjectileInfo {
+					damage: Either::Left(50),
+					armor_piercing: false,
+					inflicts: Vec::new(),
+					multi_hit: false,
+					passes_cover: false,
+					size: 100,
+					movement: Movement {
+						speed: 100.0,
+						lifetime_ms: 1000,
+						acceleration: 0.0,
+						acceleration_delay_ms: 0,
+						speed_clamp: None,
+						path: Path::Straight {
+							boomerang: false,
+							amplitude: 0.0,
+							frequency: 1.0,
+						},
+					},
+				},
+			)]
+			.into(),
+		},
+	);
+
+	Rotmguard {
+		config,
+		assets,
+		rotmg_servers: Default::default(),
+		stats: Default::default(),
+		damage_monitor_http: crate::damage_monitor_http_server::DamageMonitorHttp::Disabled,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		config::Threshold,
+		packet_logger::parse_capture,
+		protocol::{MapInfo, Notification, NotificationKind, Packet, Reconnect, S2CPacket},
+		proxy::logic::{
+			errors::{FeatureId, run_feature},
+			feature::Verdict,
+			version::SUPPORTED_BUILD_VERSIONS,
+		},
+		util::{PACKET_ID, STAT_TYPE, create_reconnect, read_str, write_compressed_int, write_str},
+	};
+	use bytes::{Buf, BufMut};
+	use std::f32::consts::PI;
+
+	const SELF_ID: u32 = 100;
+	const ENEMY_ID: u32 = 200;
+
+	fn packet(id: u8, f: impl FnOnce(&mut BytesMut)) -> BytesMut {
+		let mut b = BytesMut::new();
+		b.put_u8(id);
+		f(&mut b);
+		b
+	}
+
+	fn player_text(text: &str) -> BytesMut {
+		packet(PACKET_ID::C2S_PLAYERTEXT, |b| write_str(text, b))
+	}
+
+	fn map_info(build_version: &str) -> BytesMut {
+		let map_info = MapInfo {
+			width: 100,
+			height: 100,
+			name: "Nexus",
+			display_name: "Nexus",
+			realm_name: "",
+			seed: 1,
+			background: 0,
+			difficulty: 0.0,
+			allow_teleport: false,
+			no_save: false,
+			show_displays: false,
+			max_players: 85,
+			game_opened_time: 0,
+			build_version,
+			background_color: 0,
+			unknown: &[],
+		};
+
+		BytesMut::from(map_info.to_bytes())
+	}
+
+	fn create_success() -> BytesMut {
+		packet(PACKET_ID::S2C_CREATE_SUCCESS, |b| {
+			b.put_u32(SELF_ID);
+			b.put_u32(1);
+			write_str("", b);
+		})
+	}
+
+	// (object type, object id, int stats)
+	type NewObject<'a> = (u16, u32, &'a [(u8, i64)]);
+
+	fn update(tiles: &[(i16, i16, u16)], objects: &[NewObject]) -> BytesMut {
+		packet(PACKET_ID::S2C_UPDATE, |b| {
+			b.put_f32(0.0);
+			b.put_f32(0.0);
+			b.put_u8(0);
+
+			write_compressed_int(tiles.len() as i64, &mut *b);
+			for &(x, y, tile) in tiles {
+				b.put_i16(x);
+				b.put_i16(y);
+				b.put_u16(tile);
+			}
+
+			write_compressed_int(objects.len() as i64, &mut *b);
+			for &(object_type, id, stats) in objects {
+				b.put_u16(object_type);
+				write_compressed_int(id as i64, &mut *b);
+				b.put_f32(0.0);
+				b.put_f32(0.0);
+				write_compressed_int(stats.len() as i64, &mut *b);
+				for &(stat_type, stat) in stats {
+					b.put_u8(stat_type);
+					write_compressed_int(stat, &mut *b);
+					write_compressed_int(-1, &mut *b);
+				}
+			}
+
+			write_compressed_int(0, &mut *b); // objects to remove
+		})
+	}
+
+	fn new_tick(tick_id: u32, tick_time: u32) -> BytesMut {
+		packet(PACKET_ID::S2C_NEWTICK, |b| {
+			b.put_u32(tick_id);
+			b.put_u32(tick_time);
+			b.put_u32(0);
+			b.put_u16(0);
+			b.put_u16(0); // statuses
+		})
+	}
+
+	fn r#move(tick_id: u32) -> BytesMut {
+		packet(PACKET_ID::C2S_MOVE, |b| {
+			b.put_u32(tick_id);
+			b.put_u32(200);
+			b.put_u16(0);
+		})
+	}
+
+	// shot somewhere away from the player
+	fn enemy_shoot(bullet_id: u16, damage: i16) -> BytesMut {
+		enemy_shoot_at(bullet_id, damage, (5.0, 5.0), 0.0)
+	}
+
+	fn enemy_shoot_at(bullet_id: u16, damage: i16, pos: (f32, f32), angle: f32) -> BytesMut {
+		packet(PACKET_ID::S2C_ENEMYSHOOT, |b| {
+			b.put_u16(bullet_id);
+			b.put_u32(ENEMY_ID);
+			b.put_u8(0);
+			b.put_f32(pos.0);
+			b.put_f32(pos.1);
+			b.put_f32(angle);
+			b.put_i16(damage);
+		})
+	}
+
+	fn player_hit(bullet_id: u16) -> BytesMut {
+		packet(PACKET_ID::C2S_PLAYERHIT, |b| {
+			b.put_u16(bullet_id);
+			b.put_u32(ENEMY_ID);
+		})
+	}
+
+	fn capture_bytes(packets: &[(Direction, &BytesMut)]) -> Vec<u8> {
+		let mut capture = Vec::new();
+		for (i, (direction, data)) in packets.iter().enumerate() {
+			capture.put_u8(match direction {
+				Direction::C2S => b'c',
+				Direction::S2C => b's',
+			});
+			capture.put_u128_le(i as u128 * 1_000_000);
+			capture.put_u32_le(data.len() as u32);
+			capture.put_slice(data);
+		}
+		capture
+	}
+
+	// joins the game with a 200 HP player and an enemy on screen, synced
+	async fn joined() -> Replay {
+		joined_with(test_rotmguard()).await
+	}
+
+	async fn joined_with(rotmguard: Rotmguard) -> Replay {
+		let mut replay = Replay::new(rotmguard).unwrap();
+
+		replay.feed(Direction::S2C, create_success()).await.unwrap();
+		replay
+			.feed(
+				Direction::S2C,
+				update(
+					&[],
+					&[
+						(
+							OBJ_PLAYER,
+							SELF_ID,
+							&[
+								(STAT_TYPE::MAX_HP, 200),
+								(STAT_TYPE::HP, 200),
+								(STAT_TYPE::LEVEL, 20),
+							],
+						),
+						(OBJ_ENEMY, ENEMY_ID, &[(STAT_TYPE::MAX_HP, 1000)]),
+					],
+				),
+			)
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, r#move(1)).await.unwrap();
+
+		replay
+	}
+
+	#[tokio::test]
+	async fn capture_format() {
+		let a = player_text("hello");
+		let b = create_success();
+		let capture = capture_bytes(&[(Direction::C2S, &a), (Direction::S2C, &b)]);
+
+		let packets = parse_capture(&capture).unwrap();
+		assert_eq!(packets.len(), 2);
+		assert_eq!(packets[0].direction, Direction::C2S);
+		assert_eq!(packets[0].data, a);
+		assert_eq!(packets[1].direction, Direction::S2C);
+		assert_eq!(packets[1].time.as_millis(), 1);
+		assert_eq!(packets[1].data, b);
+
+		assert!(parse_capture(&capture[..capture.len() - 1]).is_err());
+	}
+
+	#[tokio::test]
+	async fn capture_file() {
+		let a = player_text("/hi");
+		let capture = capture_bytes(&[(Direction::C2S, &a)]);
+
+		let file = tempfile::NamedTempFile::new().unwrap();
+		std::fs::write(file.path(), capture).unwrap();
+
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+		replay
+			.capture(crate::packet_logger::read_capture(file.path()).unwrap())
+			.await
+			.unwrap();
+
+		assert_eq!(
+			replay.sent_ids(Direction::S2C),
+			[PACKET_ID::S2C_NOTIFICATION]
+		);
+		assert!(replay.to_server.is_empty());
+	}
+
+	#[tokio::test]
+	async fn passthrough() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+
+		let chat = player_text("hello there");
+		replay.feed(Direction::C2S, chat.clone()).await.unwrap();
+		let unknown = packet(PACKET_ID::S2C_PING, |b| b.put_u32(1234));
+		replay.feed(Direction::S2C, unknown.clone()).await.unwrap();
+
+		assert_eq!(replay.to_server, [chat.freeze()]);
+		assert_eq!(replay.to_client, [unknown.freeze()]);
+	}
+
+	#[tokio::test]
+	async fn command_blocked() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+
+		replay
+			.feed(Direction::C2S, player_text("/hi"))
+			.await
+			.unwrap();
+
+		assert!(replay.to_server.is_empty());
+		let mut notification = &replay.to_client[0][..];
+		assert_eq!(notification.get_u8(), PACKET_ID::S2C_NOTIFICATION);
+		notification.advance(2);
+		assert_eq!(
+			read_str(crate::util::View(&BytesMut::from(notification), &mut 0)).unwrap(),
+			"hi :)"
+		);
+	}
+
+	// text of a notification sent to the client
+	fn notification_text(packet: &Bytes) -> String {
+		let b = BytesMut::from(&packet[..]);
+		match Notification::decode(&b, &mut 1).unwrap().kind {
+			NotificationKind::Text { text, .. } => text.to_owned(),
+			other => panic!("not a text notification: {other:?}"),
+		}
+	}
+
+	#[tokio::test]
+	async fn commands_not_ours() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+
+		// game commands, and text that only looks a bit like our commands
+		let texts = ["/tell someone /hi", "/trade x", "hi /hi", "/ hi", "/"];
+		for text in texts {
+			replay
+				.feed(Direction::C2S, player_text(text))
+				.await
+				.unwrap();
+		}
+
+		assert_eq!(replay.to_server.len(), texts.len());
+		assert!(replay.to_client.is_empty());
+	}
+
+	#[tokio::test]
+	async fn command_usage() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+
+		for text in [
+			"/autonexus abc",
+			"/con",
+			"/HI there",
+			"/help ap",
+			"/help",
+			"/help 2",
+			"/dmg",
+		] {
+			replay
+				.feed(Direction::C2S, player_text(text))
+				.await
+				.unwrap();
+		}
+
+		assert!(replay.to_server.is_empty());
+		let texts: Vec<String> = replay.to_client.iter().map(notification_text).collect();
+		assert_eq!(
+			texts[0],
+			"abc is not a whole number or a percentage like 30%\nUsage: /autonexus [HP]"
+		);
+		assert_eq!(texts[1], "Missing server\nUsage: /con <server>");
+		assert_eq!(texts[2], "Too many arguments\nUsage: /hi");
+		assert_eq!(texts[3], "/ap\nToggles anti push\nAlso: /antipush");
+		assert!(texts[4].starts_with("Commands (1/3):\n/help - "));
+		assert!(texts[5].starts_with("Commands (2/3):\n"));
+		// the damage monitor is disabled in tests
+		assert_eq!(texts[6], "The damage monitor is disabled in rotmguard.toml");
+	}
+
+	#[tokio::test]
+	async fn antipush() {
+		let mut replay = joined().await;
+
+		replay
+			.feed(Direction::C2S, player_text("/ap"))
+			.await
+			.unwrap();
+		replay
+			.feed(
+				Direction::S2C,
+				update(&[(5, 5, TILE_CONVEYOR), (6, 5, TILE_GRASS)], &[]),
+			)
+			.await
+			.unwrap();
+
+		// replaced in place, and then appended again since the toggle left it unsynced
+		let sent = replay.to_client.last().unwrap();
+		assert_eq!(
+			*sent,
+			update(
+				&[(5, 5, TILE_WEB), (6, 5, TILE_GRASS), (5, 5, TILE_WEB)],
+				&[]
+			)
+			.freeze()
+		);
+
+		// disabling sends the original tiles back with the next update
+		replay
+			.feed(Direction::C2S, player_text("/ap"))
+			.await
+			.unwrap();
+		replay.feed(Direction::S2C, update(&[], &[])).await.unwrap();
+
+		let sent = replay.to_client.last().unwrap();
+		assert_eq!(*sent, update(&[(5, 5, TILE_CONVEYOR)], &[]).freeze());
+	}
+
+	#[tokio::test]
+	async fn autonexus() {
+		let mut replay = joined().await;
+
+		replay
+			.feed(Direction::S2C, enemy_shoot(1, 150))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(1)).await.unwrap();
+
+		// 50 HP left, above the threshold
+		assert_eq!(
+			replay.sent_ids(Direction::C2S),
+			[PACKET_ID::C2S_MOVE, PACKET_ID::C2S_PLAYERHIT]
+		);
+
+		replay
+			.feed(Direction::S2C, enemy_shoot(2, 40))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(2)).await.unwrap();
+
+		// 10 HP left, below the threshold
+		assert_eq!(
+			replay.sent_ids(Direction::C2S),
+			[
+				PACKET_ID::C2S_MOVE,
+				PACKET_ID::C2S_PLAYERHIT,
+				PACKET_ID::C2S_ESCAPE,
+				PACKET_ID::C2S_PLAYERHIT
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn predictive_autonexus() {
+		let mut replay = joined().await;
+		let settings = &replay.proxy().rotmguard.config.settings;
+		*settings.autonexus_hp.lock().unwrap() = Threshold::Percent(10.0);
+		*settings.autonexus_predict.lock().unwrap() = 5.0;
+
+		replay
+			.feed(Direction::S2C, enemy_shoot(1, 60))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(1)).await.unwrap();
+
+		// 140 HP left, would still have 80 after another 60
+		assert!(
+			!replay
+				.sent_ids(Direction::C2S)
+				.contains(&PACKET_ID::C2S_ESCAPE)
+		);
+
+		replay
+			.feed(Direction::S2C, enemy_shoot(2, 60))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(2)).await.unwrap();
+
+		// 80 HP left, well above 10% (20 HP), but the last 120 damage again would be deadly
+		assert_eq!(
+			replay.sent_ids(Direction::C2S),
+			[
+				PACKET_ID::C2S_MOVE,
+				PACKET_ID::C2S_PLAYERHIT,
+				PACKET_ID::C2S_ESCAPE,
+				PACKET_ID::C2S_PLAYERHIT
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn incoming_bullets() {
+		let mut replay = joined().await;
+		*replay
+			.proxy()
+			.rotmguard
+			.config
+			.settings
+			.autonexus_predict
+			.lock()
+			.unwrap() = 1.0;
+
+		// flying away from the player, who is at 0,0
+		replay
+			.feed(Direction::S2C, enemy_shoot_at(1, 190, (-3.0, 0.0), PI))
+			.await
+			.unwrap();
+		assert!(
+			!replay
+				.sent_ids(Direction::C2S)
+				.contains(&PACKET_ID::C2S_ESCAPE)
+		);
+
+		// 10 tiles per second, reaches the player in 300ms. Nexus before it lands
+		replay
+			.feed(Direction::S2C, enemy_shoot_at(2, 190, (-3.0, 0.0), 0.0))
+			.await
+			.unwrap();
+		assert_eq!(
+			replay.sent_ids(Direction::C2S),
+			[PACKET_ID::C2S_MOVE, PACKET_ID::C2S_ESCAPE]
+		);
+	}
+
+	fn escapes(replay: &Replay) -> usize {
+		replay
+			.sent_ids(Direction::C2S)
+			.iter()
+			.filter(|&&id| id == PACKET_ID::C2S_ESCAPE)
+			.count()
+	}
+
+	#[tokio::test]
+	async fn escape_retried() {
+		let mut replay = joined().await;
+
+		for bullet_id in [1, 2] {
+			replay
+				.feed(Direction::S2C, enemy_shoot(bullet_id, 100))
+				.await
+				.unwrap();
+			replay
+				.feed(Direction::C2S, player_hit(bullet_id))
+				.await
+				.unwrap();
+		}
+		// not again for every hit
+		assert_eq!(escapes(&replay), 1);
+
+		replay.feed(Direction::S2C, new_tick(1, 600)).await.unwrap();
+		assert_eq!(escapes(&replay), 1);
+		replay.feed(Direction::S2C, new_tick(2, 600)).await.unwrap();
+		assert_eq!(escapes(&replay), 2);
+
+		// the server is sending us to the nexus
+		let reconnect = create_reconnect("Nexus", "", 2050, 0xfffffffe, 0, &[]);
+		replay
+			.feed(Direction::S2C, BytesMut::from(reconnect))
+			.await
+			.unwrap();
+		replay
+			.feed(Direction::S2C, new_tick(3, 2000))
+			.await
+			.unwrap();
+		assert_eq!(escapes(&replay), 2);
+	}
+
+	#[tokio::test]
+	async fn escape_fallback() {
+		let mut rotmguard = test_rotmguard();
+		rotmguard
+			.rotmg_servers
+			.insert("usw".to_owned(), "127.0.0.2".to_owned());
+		*rotmguard.config.settings.autonexus_fallback.lock().unwrap() = Some("usw".to_owned());
+		let mut replay = joined_with(rotmguard).await;
+
+		replay
+			.feed(Direction::S2C, enemy_shoot(1, 190))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(1)).await.unwrap();
+
+		for tick_id in 1..=3 {
+			replay
+				.feed(Direction::S2C, new_tick(tick_id, 1000))
+				.await
+				.unwrap();
+		}
+		assert_eq!(escapes(&replay), 3);
+		assert_ne!(
+			replay.sent_ids(Direction::S2C).last(),
+			Some(&PACKET_ID::S2C_RECONNECT)
+		);
+
+		// resent twice, now reconnecting the client ourselves
+		replay
+			.feed(Direction::S2C, new_tick(4, 1000))
+			.await
+			.unwrap();
+		let reconnect = replay
+			.to_client
+			.iter()
+			.find(|p| p[0] == PACKET_ID::S2C_RECONNECT)
+			.unwrap();
+		let packet = BytesMut::from(reconnect.clone());
+		let c = &mut 1;
+		assert_eq!(Reconnect::decode(&packet, c).unwrap().address, "127.0.0.2");
+		assert_eq!(escapes(&replay), 3);
+
+		// not again while the reconnect is happening
+		replay
+			.feed(Direction::S2C, enemy_shoot(2, 5))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(2)).await.unwrap();
+		assert_eq!(escapes(&replay), 3);
+
+		// but it works again once we're somewhere else
+		replay
+			.feed(Direction::S2C, map_info(SUPPORTED_BUILD_VERSIONS[0]))
+			.await
+			.unwrap();
+		replay
+			.feed(Direction::S2C, enemy_shoot(3, 5))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(3)).await.unwrap();
+		assert_eq!(escapes(&replay), 4);
+	}
+
+	#[tokio::test]
+	async fn unknown_fallback() {
+		let rotmguard = test_rotmguard();
+		*rotmguard.config.settings.autonexus_fallback.lock().unwrap() = Some("usw".to_owned());
+		let mut replay = joined_with(rotmguard).await;
+
+		replay
+			.feed(Direction::S2C, enemy_shoot(1, 190))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(1)).await.unwrap();
+		for tick_id in 1..=4 {
+			replay
+				.feed(Direction::S2C, new_tick(tick_id, 1000))
+				.await
+				.unwrap();
+		}
+		assert_eq!(escapes(&replay), 3);
+		assert!(
+			!replay
+				.to_client
+				.iter()
+				.any(|p| p[0] == PACKET_ID::S2C_RECONNECT)
+		);
+
+		// nothing was done, so still trying to get away
+		replay
+			.feed(Direction::S2C, enemy_shoot(2, 5))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(2)).await.unwrap();
+		assert_eq!(escapes(&replay), 4);
+	}
+
+	// whether /hi gets handled after joining with the given version
+	async fn handles_commands(rotmguard: Rotmguard, build_version: &str) -> bool {
+		let mut replay = Replay::new(rotmguard).unwrap();
+
+		replay
+			.feed(Direction::S2C, map_info(build_version))
+			.await
+			.unwrap();
+		replay
+			.feed(Direction::C2S, player_text("/hi"))
+			.await
+			.unwrap();
+
+		replay.to_server.is_empty()
+	}
+
+	#[tokio::test]
+	async fn supported_version() {
+		assert!(handles_commands(test_rotmguard(), SUPPORTED_BUILD_VERSIONS[0]).await);
+		assert!(!handles_commands(test_rotmguard(), "1.2.3").await);
+
+		let rotmguard = test_rotmguard();
+		*rotmguard
+			.config
+			.settings
+			.extra_build_versions
+			.lock()
+			.unwrap() = vec!["1.2.3".to_owned()];
+		assert!(handles_commands(rotmguard, "1.2.3").await);
+	}
+
+	#[tokio::test]
+	async fn unsupported_version() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+
+		replay
+			.feed(Direction::S2C, map_info("0.0.0.0.0"))
+			.await
+			.unwrap();
+
+		// everything forwarded untouched, including commands
+		let hi = player_text("/hi");
+		replay.feed(Direction::C2S, hi.clone()).await.unwrap();
+		assert_eq!(replay.to_server, [hi.freeze()]);
+
+		// the player is told once in game
+		replay.feed(Direction::S2C, create_success()).await.unwrap();
+		replay.feed(Direction::S2C, create_success()).await.unwrap();
+		assert_eq!(
+			replay.sent_ids(Direction::S2C),
+			[
+				PACKET_ID::S2C_MAPINFO,
+				PACKET_ID::S2C_CREATE_SUCCESS,
+				PACKET_ID::S2C_NOTIFICATION,
+				PACKET_ID::S2C_CREATE_SUCCESS,
+			]
+		);
+
+		// and no autonexus on data we dont understand
+		replay
+			.feed(
+				Direction::S2C,
+				update(&[], &[(OBJ_PLAYER, SELF_ID, &[(STAT_TYPE::HP, 1)])]),
+			)
+			.await
+			.unwrap();
+		replay
+			.feed(Direction::S2C, enemy_shoot(1, 500))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(1)).await.unwrap();
+		assert!(
+			!replay
+				.sent_ids(Direction::C2S)
+				.contains(&PACKET_ID::C2S_ESCAPE)
+		);
+	}
+
+	#[tokio::test]
+	async fn unknown_map_info_layout() {
+		let mut replay = Replay::new(test_rotmguard()).unwrap();
+
+		let mut truncated = map_info(SUPPORTED_BUILD_VERSIONS[0]);
+		truncated.truncate(20);
+		replay
+			.feed(Direction::S2C, truncated.clone())
+			.await
+			.unwrap();
+
+		let hi = player_text("/hi");
+		replay.feed(Direction::C2S, hi.clone()).await.unwrap();
+
+		assert_eq!(replay.to_client, [truncated.freeze()]);
+		assert_eq!(replay.to_server, [hi.freeze()]);
+	}
+
+	fn ground_damage() -> BytesMut {
+		packet(PACKET_ID::C2S_GROUNDDAMAGE, |b| {
+			b.put_u32(0);
+			b.put_f32(10.0);
+			b.put_f32(10.0);
+		})
+	}
+
+	#[tokio::test]
+	async fn parse_error_forwards_original() {
+		let mut replay = joined().await;
+
+		replay
+			.feed(Direction::C2S, player_text("/ap"))
+			.await
+			.unwrap();
+
+		// the conveyor tile gets replaced in place before the error is hit
+		let mut broken = update(&[(5, 5, TILE_CONVEYOR)], &[]);
+		broken.truncate(broken.len() - 1);
+		replay.feed(Direction::S2C, broken.clone()).await.unwrap();
+		assert_eq!(*replay.to_client.last().unwrap(), broken.freeze());
+
+		// and everything keeps working
+		replay
+			.feed(Direction::C2S, player_text("/hi"))
+			.await
+			.unwrap();
+		assert_eq!(
+			replay.sent_ids(Direction::S2C).last(),
+			Some(&PACKET_ID::S2C_NOTIFICATION)
+		);
+	}
+
+	#[tokio::test]
+	async fn failed_hook_undone() {
+		async fn failing(packet: &mut S2CPacket<'_>, verdict: &mut Verdict) -> anyhow::Result<()> {
+			if let S2CPacket::Update(update) = packet {
+				update.tiles[0].tile_type = 0;
+			}
+			verdict.modified = true;
+			verdict.block = true;
+
+			anyhow::bail!("failed halfway")
+		}
+
+		let mut replay = joined().await;
+		let proxy = replay.proxy();
+
+		let bytes = update(&[(5, 5, TILE_CONVEYOR)], &[]);
+		let mut packet = S2CPacket::decode(bytes[0], &bytes, &mut 1)
+			.unwrap()
+			.unwrap();
+		let packet = &mut packet;
+		let verdict = &mut Verdict::default();
+
+		run_feature!(
+			proxy,
+			FeatureId::AntiPush,
+			failing(packet, verdict).await,
+			verdict: verdict
+		);
+
+		// not blocked, and forwarded as it was received
+		assert!(!verdict.block);
+		assert!(!verdict.encode());
+		assert!(verdict.failed);
+	}
+
+	#[tokio::test]
+	async fn feature_disabled_after_errors() {
+		let mut replay = joined().await;
+
+		// not standing on anything hazardous
+		for _ in 0..4 {
+			replay.feed(Direction::C2S, ground_damage()).await.unwrap();
+		}
+		assert_eq!(replay.to_client.len(), 2); // create success and update
+		replay.feed(Direction::C2S, ground_damage()).await.unwrap();
+		assert_eq!(
+			replay.sent_ids(Direction::S2C).last(),
+			Some(&PACKET_ID::S2C_NOTIFICATION)
+		);
+		assert_eq!(
+			replay.sent_ids(Direction::C2S)[1..],
+			[PACKET_ID::C2S_GROUNDDAMAGE; 5]
+		);
+
+		// no more autonexus
+		replay
+			.feed(Direction::S2C, enemy_shoot(1, 190))
+			.await
+			.unwrap();
+		replay.feed(Direction::C2S, player_hit(1)).await.unwrap();
+		assert!(
+			!replay
+				.sent_ids(Direction::C2S)
+				.contains(&PACKET_ID::C2S_ESCAPE)
+		);
+	}
+}
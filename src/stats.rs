@@ -9,6 +9,8 @@ use tracing::info;
 pub struct Stats {
 	s2c: DirectionStats,
 	c2s: DirectionStats,
+	// errors while handling packets, which were recovered from
+	errors: AtomicU64,
 }
 
 #[derive(Default)]
@@ -39,6 +41,9 @@ impl Stats {
 				.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
 		}
 	}
+	pub fn add_error(&self) {
+		self.errors.fetch_add(1, Ordering::Relaxed);
+	}
 }
 
 impl Drop for Stats {
@@ -62,5 +67,10 @@ impl Drop for Stats {
 	- Avg flush delay: {avg_delay:.2}us"
 			);
 		}
+
+		let errors = self.errors.load(Ordering::Relaxed);
+		if errors > 0 {
+			info!("Recovered from {errors} errors while handling packets");
+		}
 	}
 }
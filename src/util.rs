@@ -1,11 +1,13 @@
 use anyhow::{Result, bail};
 use bytes::{Buf, BufMut};
 
+mod conditions;
 mod create_packet;
 mod packet_ids;
 mod stat_types;
 mod view;
 
+pub use conditions::*;
 pub use create_packet::*;
 pub use packet_ids::*;
 pub use stat_types::*;
@@ -36,7 +38,7 @@ pub fn write_str(data: &str, mut out: impl BufMut) {
 	out.put_slice(data.as_bytes());
 }
 pub fn size_as_compressed_int(value: i64) -> usize {
-	let value = value.abs() as u64;
+	let value = value.unsigned_abs();
 
 	let bits_needed = 64 - value.leading_zeros() as usize;
 	// first byte fits only 6 bits because of sign flag
@@ -53,7 +55,8 @@ pub fn read_compressed_int(mut view: View) -> Result<i64> {
 
 	let is_negative = (byte & 0x40) != 0;
 	let mut shift = 6;
-	let mut value = (byte & 0x3f) as i64;
+	// magnitude, only i64::MIN needs all 64 bits
+	let mut value = (byte & 0x3f) as u64;
 
 	while (byte & 0x80) != 0 {
 		if shift >= 64 {
@@ -61,15 +64,16 @@ pub fn read_compressed_int(mut view: View) -> Result<i64> {
 		}
 
 		byte = view.try_get_u8()?;
-		value |= ((byte & 0x7f) as i64) << shift;
+		value |= ((byte & 0x7f) as u64) << shift;
 		shift += 7;
 	}
 
+	let value = value as i64;
 	if is_negative {
-		value = -value;
+		Ok(value.wrapping_neg())
+	} else {
+		Ok(value)
 	}
-
-	Ok(value)
 }
 pub fn write_compressed_int(value: i64, out: impl BufMut) {
 	let size = size_as_compressed_int(value);
@@ -90,7 +94,7 @@ pub fn write_compressed_int_exact_size(value: i64, exact_size: usize, mut out: i
 	if value.is_negative() {
 		buf[0] |= 0x40;
 	}
-	let mut value = value.abs();
+	let mut value = value.unsigned_abs();
 
 	let first_byte = (value & 0x3f) as u8;
 	value >>= 6;
@@ -103,3 +107,173 @@ pub fn write_compressed_int_exact_size(value: i64, exact_size: usize, mut out: i
 
 	out.put_slice(&buf[..exact_size]);
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bytes::BytesMut;
+	use proptest::prelude::*;
+
+	fn encode(value: i64) -> BytesMut {
+		let mut buf = BytesMut::new();
+		write_compressed_int(value, &mut buf);
+		buf
+	}
+
+	// returns the value and how many bytes were read
+	fn decode(bytes: &[u8]) -> Result<(i64, usize)> {
+		let buf = BytesMut::from(bytes);
+		let c = &mut 0;
+		let value = read_compressed_int(View(&buf, c))?;
+
+		Ok((value, *c))
+	}
+
+	#[test]
+	fn known_encodings() {
+		for (value, bytes) in [
+			(0, &[0x00][..]),
+			(1, &[0x01]),
+			(-1, &[0x41]),
+			(63, &[0x3f]),
+			(-63, &[0x7f]),
+			(64, &[0x80, 0x01]),
+			(-64, &[0xc0, 0x01]),
+			(8191, &[0xbf, 0x7f]),
+			(8192, &[0x80, 0x80, 0x01]),
+		] {
+			assert_eq!(&encode(value)[..], bytes, "encoding {value}");
+			assert_eq!(decode(bytes).unwrap(), (value, bytes.len()));
+		}
+	}
+
+	#[test]
+	fn extremes() {
+		for value in [i64::MAX, i64::MIN, i64::MIN + 1] {
+			let buf = encode(value);
+			assert_eq!(buf.len(), 10);
+			assert_eq!(size_as_compressed_int(value), 10);
+			assert_eq!(decode(&buf).unwrap(), (value, 10));
+		}
+	}
+
+	#[test]
+	fn varint_too_long() {
+		// 10 bytes is the maximum
+		let mut bytes = [0x80; 11];
+		bytes[10] = 0;
+		let err = decode(&bytes).unwrap_err();
+		assert_eq!(err.to_string(), "Varint too long");
+
+		bytes[9] = 0;
+		assert_eq!(decode(&bytes).unwrap(), (0, 10));
+	}
+
+	#[test]
+	fn varint_truncated() {
+		assert!(decode(&[]).is_err());
+		assert!(decode(&[0x80]).is_err());
+		assert!(decode(&[0xff, 0xff]).is_err());
+	}
+
+	#[test]
+	#[should_panic]
+	fn exact_size_too_small() {
+		write_compressed_int_exact_size(64, 1, BytesMut::new());
+	}
+
+	#[test]
+	fn strings() {
+		let mut buf = BytesMut::new();
+		write_str("", &mut buf);
+		write_str("žodis", &mut buf);
+		assert_eq!(&buf[..2], &[0, 0]);
+
+		let c = &mut 0;
+		assert_eq!(read_str(View(&buf, c)).unwrap(), "");
+		assert_eq!(read_str(View(&buf, c)).unwrap(), "žodis");
+		assert_eq!(*c, buf.len());
+
+		// length longer than the data
+		let buf = BytesMut::from(&[0, 5, b'a', b'b'][..]);
+		let err = read_str(View(&buf, &mut 0)).unwrap_err();
+		assert_eq!(err.to_string(), "not enough bytes to fit 5 long string");
+
+		// invalid utf-8
+		let buf = BytesMut::from(&[0, 2, 0xc3, 0x28][..]);
+		assert!(read_str(View(&buf, &mut 0)).is_err());
+	}
+
+	#[test]
+	#[should_panic]
+	fn string_too_long() {
+		write_str(&"a".repeat(u16::MAX as usize + 1), BytesMut::new());
+	}
+
+	proptest! {
+		#[test]
+		fn varint_round_trip(value: i64) {
+			let buf = encode(value);
+
+			prop_assert_eq!(buf.len(), size_as_compressed_int(value));
+			prop_assert_eq!(decode(&buf).unwrap(), (value, buf.len()));
+		}
+
+		#[test]
+		fn varint_exact_size(value: i64, padding in 0usize..10) {
+			let natural_size = size_as_compressed_int(value);
+			let exact_size = (natural_size + padding).min(10);
+
+			let mut buf = BytesMut::new();
+			write_compressed_int_exact_size(value, exact_size, &mut buf);
+
+			prop_assert_eq!(buf.len(), exact_size);
+			prop_assert_eq!(decode(&buf).unwrap(), (value, exact_size));
+		}
+
+		#[test]
+		fn varint_in_place_overwrite(old: i64, new: i64) {
+			// the way stats get replaced in NewTick/Update
+			prop_assume!(size_as_compressed_int(new) <= size_as_compressed_int(old));
+
+			let mut buf = encode(old);
+			buf.extend_from_slice(&encode(12345));
+			let old_size = size_as_compressed_int(old);
+			write_compressed_int_exact_size(new, old_size, &mut buf[..]);
+
+			let c = &mut 0;
+			prop_assert_eq!(read_compressed_int(View(&buf, c)).unwrap(), new);
+			prop_assert_eq!(*c, old_size);
+			prop_assert_eq!(read_compressed_int(View(&buf, c)).unwrap(), 12345);
+		}
+
+		#[test]
+		fn varint_arbitrary_bytes(bytes: Vec<u8>) {
+			// must never panic, and never read out of bounds
+			if let Ok((_, read)) = decode(&bytes) {
+				prop_assert!(read <= bytes.len());
+				prop_assert!(read <= 10);
+			}
+		}
+
+		#[test]
+		fn string_round_trip(s in ".{0,300}") {
+			let mut buf = BytesMut::new();
+			write_str(&s, &mut buf);
+			prop_assert_eq!(buf.len(), 2 + s.len());
+
+			let c = &mut 0;
+			prop_assert_eq!(read_str(View(&buf, c)).unwrap(), s.as_str());
+			prop_assert_eq!(*c, buf.len());
+		}
+
+		#[test]
+		fn string_arbitrary_bytes(bytes: Vec<u8>) {
+			let buf = BytesMut::from(&bytes[..]);
+			let c = &mut 0;
+			if let Ok(s) = read_str(View(&buf, c)) {
+				prop_assert_eq!(*c, 2 + s.len());
+			}
+		}
+	}
+}
@@ -0,0 +1,172 @@
+//! All condition effects, by the id used in packets and the name used in the assets.
+
+pub struct ConditionEffect {
+	pub id: u8,
+	/// As written in the `ConditionEffect` elements of the assets
+	pub name: &'static str,
+	/// Other spellings found in the assets
+	pub aliases: &'static [&'static str],
+}
+
+const fn effect(id: u8, name: &'static str) -> ConditionEffect {
+	ConditionEffect {
+		id,
+		name,
+		aliases: &[],
+	}
+}
+
+pub const CONDITION_EFFECTS: &[ConditionEffect] = &[
+	effect(1, "Dead"),
+	effect(2, "Quiet"),
+	effect(3, "Weak"),
+	ConditionEffect {
+		id: 4,
+		name: "Slowed",
+		aliases: &["Slow"],
+	},
+	effect(5, "Sick"),
+	effect(6, "Dazed"),
+	effect(7, "Stunned"),
+	effect(8, "Blind"),
+	effect(9, "Hallucinating"),
+	effect(10, "Drunk"),
+	effect(11, "Confused"),
+	effect(12, "Stun Immune"),
+	effect(13, "Invisible"),
+	ConditionEffect {
+		id: 14,
+		name: "Paralyzed",
+		aliases: &["Paralysed"],
+	},
+	effect(15, "Speedy"),
+	effect(16, "Bleeding"),
+	effect(17, "Armor Broken Immune"),
+	effect(18, "Healing"),
+	effect(19, "Damaging"),
+	effect(20, "Berserk"),
+	effect(21, "In Combat"),
+	effect(22, "Stasis"),
+	effect(23, "Stasis Immune"),
+	effect(24, "Invincible"),
+	effect(25, "Invulnerable"),
+	effect(26, "Armored"),
+	effect(27, "Armor Broken"),
+	effect(28, "Hexed"),
+	effect(29, "Ninja Speedy"),
+	effect(30, "Unstable"),
+	effect(31, "Darkness"),
+	effect(32, "Slowed Immune"),
+	effect(33, "Dazed Immune"),
+	effect(34, "Paralyzed Immune"),
+	ConditionEffect {
+		id: 35,
+		name: "Petrify",
+		aliases: &["Petrified"],
+	},
+	ConditionEffect {
+		id: 36,
+		name: "Petrify Immune",
+		aliases: &["Petrified Immune"],
+	},
+	effect(37, "Pet Effect Icon"),
+	ConditionEffect {
+		id: 38,
+		name: "Curse",
+		aliases: &["Cursed"],
+	},
+	effect(39, "Curse Immune"),
+	effect(40, "HP Boost"),
+	effect(41, "MP Boost"),
+	effect(42, "Att Boost"),
+	effect(43, "Def Boost"),
+	effect(44, "Spd Boost"),
+	effect(45, "Vit Boost"),
+	effect(46, "Wis Boost"),
+	effect(47, "Dex Boost"),
+	effect(48, "Silenced"),
+	effect(49, "Exposed"),
+	effect(50, "Energized"),
+	effect(61, "Drought"),
+	effect(62, "Lethal Strike"),
+];
+
+impl ConditionEffect {
+	pub fn by_id(id: u8) -> Option<&'static ConditionEffect> {
+		CONDITION_EFFECTS.iter().find(|effect| effect.id == id)
+	}
+	/// Ignores case and spaces
+	pub fn by_name(name: &str) -> Option<&'static ConditionEffect> {
+		let name = normalize(name);
+
+		CONDITION_EFFECTS.iter().find(|effect| {
+			normalize(effect.name) == name || effect.aliases.iter().any(|a| normalize(a) == name)
+		})
+	}
+	/// The bit of this condition in the (CONDITION, CONDITION2) stats
+	pub fn bitflags(&self) -> (u64, u64) {
+		if self.id < 32 {
+			(1 << (self.id - 1), 0)
+		} else {
+			(0, 1 << (self.id - 32))
+		}
+	}
+}
+
+/// Names of all conditions that are set in the given stats
+pub fn condition_names(condition: u64, condition2: u64) -> Vec<&'static str> {
+	CONDITION_EFFECTS
+		.iter()
+		.filter(|effect| {
+			let (c, c2) = effect.bitflags();
+			(condition & c) != 0 || (condition2 & c2) != 0
+		})
+		.map(|effect| effect.name)
+		.collect()
+}
+
+fn normalize(name: &str) -> String {
+	name.chars()
+		.filter(|c| !c.is_whitespace())
+		.map(|c| c.to_ascii_lowercase())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::{CONDITION_BITFLAG, CONDITION2_BITFLAG};
+
+	#[test]
+	fn table() {
+		let bits = |name| ConditionEffect::by_name(name).unwrap().bitflags();
+
+		assert_eq!(bits("Weak"), (CONDITION_BITFLAG::WEAK, 0));
+		assert_eq!(bits("Slow"), (CONDITION_BITFLAG::SLOW, 0));
+		assert_eq!(bits("ArmorBroken"), (CONDITION_BITFLAG::ARMOR_BROKEN, 0));
+		assert_eq!(bits("darkness"), (CONDITION_BITFLAG::DARKNESS, 0));
+		assert_eq!(bits("Petrify"), (0, CONDITION2_BITFLAG::PETRIFIED));
+		assert_eq!(bits("Curse"), (0, CONDITION2_BITFLAG::CURSED));
+		assert_eq!(bits("Exposed"), (0, CONDITION2_BITFLAG::EXPOSED));
+		assert_eq!(
+			bits("Lethal Strike"),
+			(0, CONDITION2_BITFLAG::LETHAL_STRIKE)
+		);
+		assert!(ConditionEffect::by_name("Nothing").is_none());
+
+		assert_eq!(ConditionEffect::by_id(7).unwrap().name, "Stunned");
+
+		assert_eq!(
+			condition_names(
+				CONDITION_BITFLAG::PARALYZED | CONDITION_BITFLAG::SLOW,
+				CONDITION2_BITFLAG::SILENCED
+			),
+			["Slowed", "Paralyzed", "Silenced"]
+		);
+
+		// no id is there twice
+		for (i, effect) in CONDITION_EFFECTS.iter().enumerate() {
+			assert!(CONDITION_EFFECTS[..i].iter().all(|e| e.id != effect.id));
+		}
+	}
+}
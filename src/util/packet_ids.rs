@@ -1,173 +1,199 @@
 // taken from https://github.com/X-com/RealmShark/blob/realmshark/src/main/java/packets/PacketType.java
 // might not be completely accurate
 
+use crate::Direction;
+
+macro_rules! packet_ids {
+	($($name:ident = $id:literal;)*) => {
+		$(pub const $name: u8 = $id;)*
+
+		/// (name, id) of all packets listed
+		pub const ALL: &[(&str, u8)] = &[$((stringify!($name), $id)),*];
+	};
+}
+
 #[allow(dead_code, non_snake_case)]
 pub mod PACKET_ID {
-	pub const C2S_TELEPORT: u8 = 1;
-	pub const C2S_CLAIM_LOGIN_REWARD_MSG: u8 = 3;
-	pub const C2S_REQUESTTRADE: u8 = 5;
-	pub const C2S_JOINGUILD: u8 = 7;
-	pub const C2S_PLAYERTEXT: u8 = 9;
-	pub const C2S_USEITEM: u8 = 13;
-	pub const C2S_GUILDREMOVE: u8 = 15;
-	pub const C2S_PETUPGRADEREQUEST: u8 = 16;
-	pub const C2S_INVDROP: u8 = 19;
-	pub const C2S_OTHERHIT: u8 = 20;
-	pub const C2S_ACTIVE_PET_UPDATE_REQUEST: u8 = 24;
-	pub const C2S_ENEMYHIT: u8 = 25;
-	pub const C2S_EDITACCOUNTLIST: u8 = 27;
-	pub const C2S_PLAYERSHOOT: u8 = 30;
-	pub const C2S_PONG: u8 = 31;
-	pub const C2S_PET_CHANGE_SKIN_MSG: u8 = 33;
-	pub const C2S_ACCEPTTRADE: u8 = 36;
-	pub const C2S_CHANGEGUILDRANK: u8 = 37;
-	pub const C2S_SQUAREHIT: u8 = 40;
-	pub const C2S_USEPORTAL: u8 = 47;
-	pub const C2S_QUEST_ROOM_MSG: u8 = 48;
-	pub const C2S_RESKIN: u8 = 51;
-	pub const C2S_RESET_DAILY_QUESTS: u8 = 52;
-	pub const C2S_PET_CHANGE_FORM_MSG: u8 = 53;
-	pub const C2S_INVSWAP: u8 = 55;
-	pub const C2S_CHANGETRADE: u8 = 56;
-	pub const C2S_CREATE: u8 = 57;
-	pub const C2S_QUEST_REDEEM: u8 = 58;
-	pub const C2S_CREATEGUILD: u8 = 59;
-	pub const C2S_SETCONDITION: u8 = 60;
-	pub const C2S_LOAD: u8 = 61;
-	pub const C2S_MOVE: u8 = 62;
-	pub const C2S_GOTOACK: u8 = 65;
-	pub const C2S_HELLO: u8 = 74;
-	pub const C2S_UPDATEACK: u8 = 81;
-	pub const C2S_BUY: u8 = 85;
-	pub const C2S_AOEACK: u8 = 89;
-	pub const C2S_PLAYERHIT: u8 = 90;
-	pub const C2S_CANCELTRADE: u8 = 91;
-	pub const C2S_KEY_INFO_REQUEST: u8 = 94;
-	pub const C2S_CHOOSENAME: u8 = 97;
-	pub const C2S_QUEST_FETCH_ASK: u8 = 98;
-	pub const C2S_CHECKCREDITS: u8 = 102;
-	pub const C2S_GROUNDDAMAGE: u8 = 103;
-	pub const C2S_GUILDINVITE: u8 = 104;
-	pub const C2S_ESCAPE: u8 = 105;
-	pub const C2S_QUEUE_CANCEL: u8 = 113;
-	pub const C2S_REDEEM_EXALTATION_REWARD: u8 = 115;
-	pub const C2S_FORGE_REQUEST: u8 = 118;
-	pub const C2S_SHOOT_ACK: u8 = 121;
-	pub const C2S_CHANGE_ALLYSHOOT: u8 = 122;
-	pub const C2S_GET_PLAYERS_LIST_MESSAGE: u8 = 123;
-	pub const C2S_MODERATOR_ACTION_MESSAGE: u8 = 124;
-	pub const C2S_CREEP_MOVE_MESSAGE: u8 = 126;
-	pub const C2S_CUSTOM_MAP_DELETE: u8 = 129;
-	pub const C2S_CUSTOM_MAP_LIST: u8 = 131;
-	pub const C2S_CREEP_HIT: u8 = 133;
-	pub const C2S_PLAYER_CALLOUT: u8 = 134;
-	pub const C2S_BUY_REFINEMENT: u8 = 136;
-	pub const C2S_DASH: u8 = 137;
-	pub const C2S_DASH_ACK: u8 = 138;
-	pub const C2S_BUY_CUSTOMISATION_SOCKET: u8 = 140;
-	pub const C2S_FAVOUR_PET: u8 = 145;
-	pub const C2S_SKIN_RECYCLE: u8 = 146;
-	pub const C2S_CLAIM_BATTLE_PASS: u8 = 149;
-	pub const C2S_BOOST_BP_MILESTONE: u8 = 151;
-	pub const C2S_CONVERT_SEASONAL_CHARACTER: u8 = 154;
-	pub const C2S_RETITLE: u8 = 155;
-	pub const C2S_SET_GRAVE_STONE: u8 = 156;
-	pub const C2S_SET_ABILITY: u8 = 157;
-	pub const C2S_EMOTE: u8 = 159;
-	pub const C2S_BUY_EMOTE: u8 = 160;
-	pub const C2S_SET_TRACKED_SEASON: u8 = 162;
-	pub const C2S_CLAIM_MISSION: u8 = 163;
-	pub const C2S_SET_DISCOVERABLE: u8 = 167;
-	pub const C2S_UNLOCK_ENCHANTMENT_SLOT: u8 = 173;
-	pub const C2S_UNLOCK_ENCHANTMENT: u8 = 175;
-	pub const C2S_APPLY_ENCHANTMENT: u8 = 177;
-	pub const C2S_ACTIVATE_CRUCIBLE: u8 = 180;
-	pub const C2S_CRUCIBLE_REQUEST: u8 = 182;
-	pub const C2S_UPGRADE_ENCHANTER: u8 = 185;
-	pub const C2S_UPGRADE_ENCHANTMENT: u8 = 187;
-	pub const C2S_REROLL_ALL_ENCHANTMENTS: u8 = 189;
-	pub const C2S_RESET_ENCHANTMENT_REROLL_COUNT: u8 = 191;
-	pub const C2S_CREATE_PARTY_MESSAGE: u8 = 200;
-	pub const C2S_PARTY_ACTION: u8 = 207;
-	pub const C2S_PARTY_INVITE_RESPONSE: u8 = 209;
-	//
-	//
-	pub const S2C_FAILURE: u8 = 0;
-	pub const S2C_DELETE_PET: u8 = 4;
-	pub const S2C_QUEST_FETCH_RESPONSE: u8 = 6;
-	pub const S2C_PING: u8 = 8;
-	pub const S2C_NEWTICK: u8 = 10;
-	pub const S2C_SHOWEFFECT: u8 = 11;
-	pub const S2C_SERVERPLAYERSHOOT: u8 = 12;
-	pub const S2C_TRADEACCEPTED: u8 = 14;
-	pub const S2C_GOTO: u8 = 18;
-	pub const S2C_NAMERESULT: u8 = 21;
-	pub const S2C_BUYRESULT: u8 = 22;
-	pub const S2C_HATCH_PET: u8 = 23;
-	pub const S2C_GUILDRESULT: u8 = 26;
-	pub const S2C_TRADECHANGED: u8 = 28;
-	pub const S2C_TRADEDONE: u8 = 34;
-	pub const S2C_ENEMYSHOOT: u8 = 35;
-	pub const S2C_PLAYSOUND: u8 = 38;
-	pub const S2C_VERIFY_EMAIL: u8 = 39;
-	pub const S2C_NEW_ABILITY: u8 = 41;
-	pub const S2C_UPDATE: u8 = 42;
-	pub const S2C_TEXT: u8 = 44;
-	pub const S2C_RECONNECT: u8 = 45;
-	pub const S2C_DEATH: u8 = 46;
-	pub const S2C_ALLYSHOOT: u8 = 49;
-	pub const S2C_KEY_INFO_RESPONSE: u8 = 63;
-	pub const S2C_AOE: u8 = 64;
-	pub const S2C_GLOBAL_NOTIFICATION: u8 = 66;
-	pub const S2C_NOTIFICATION: u8 = 67;
-	pub const S2C_CLIENTSTAT: u8 = 69;
-	pub const S2C_DAMAGE: u8 = 75;
-	pub const S2C_ACTIVEPETUPDATE: u8 = 76;
-	pub const S2C_INVITEDTOGUILD: u8 = 77;
-	pub const S2C_PETYARDUPDATE: u8 = 78;
-	pub const S2C_PASSWORD_PROMPT: u8 = 79;
-	pub const S2C_QUESTOBJID: u8 = 82;
-	pub const S2C_PIC: u8 = 83;
-	pub const S2C_REALM_HERO_LEFT_MSG: u8 = 84;
-	pub const S2C_TRADESTART: u8 = 86;
-	pub const S2C_EVOLVE_PET: u8 = 87;
-	pub const S2C_TRADEREQUESTED: u8 = 88;
-	pub const S2C_MAPINFO: u8 = 92;
-	pub const S2C_LOGIN_REWARD_MSG: u8 = 93;
-	pub const S2C_INVRESULT: u8 = 95;
-	pub const S2C_QUEST_REDEEM_RESPONSE: u8 = 96;
-	pub const S2C_ACCOUNTLIST: u8 = 99;
-	pub const S2C_CREATE_SUCCESS: u8 = 101;
-	pub const S2C_FILE: u8 = 106;
-	pub const S2C_RESKIN_UNLOCK: u8 = 107;
-	pub const S2C_NEW_CHARACTER_INFORMATION: u8 = 108;
-	pub const S2C_UNLOCK_INFORMATION: u8 = 109;
-	pub const S2C_QUEUE_INFORMATION: u8 = 112;
-	pub const S2C_EXALTATION_BONUS_CHANGED: u8 = 114;
-	pub const S2C_VAULT_UPDATE: u8 = 117;
-	pub const S2C_FORGE_RESULT: u8 = 119;
-	pub const S2C_FORGE_UNLOCKED_BLUEPRINTS: u8 = 120;
-	pub const S2C_STATS: u8 = 139;
-	pub const S2C_UNKNOWN147: u8 = 147;
-	pub const S2C_DAMAGE_BOOST: u8 = 148;
-	pub const S2C_CLAIM_BP_MILESTONE_RESULT: u8 = 150;
-	pub const S2C_UNKNOWN164: u8 = 164;
-	pub const S2C_UNKNOWN165: u8 = 165;
-	pub const S2C_STASIS: u8 = 166;
-	pub const S2C_REALM_SCORE_UPDATE: u8 = 169;
-	pub const S2C_CLAIM_REWARDS_INFO_PROMPT: u8 = 170;
-	pub const S2C_CLAIM_CHEST_REWARD: u8 = 171;
-	pub const S2C_CHEST_REWARD_RESULT: u8 = 172;
-	pub const S2C_UNKNOWN181: u8 = 181;
-	pub const S2C_CRUCIBLE_RESPONSE: u8 = 183;
-	pub const S2C_UNKNOWN190: u8 = 190;
-	pub const S2C_PARTY_ACTION_RESULT: u8 = 204;
-	pub const S2C_INCOMING_PARTY_INVITE: u8 = 208;
-	pub const S2C_INCOMING_PARTY_MEMBER_INFO: u8 = 210;
-	pub const S2C_PARTY_MEMBER_ADDED: u8 = 212;
-	pub const S2C_PARTY_LIST_MESSAGE: u8 = 214;
-	pub const S2C_PARTY_JOIN_REQUEST: u8 = 215;
-	pub const S2C_PARTY_REQUEST_RESPONSE: u8 = 217;
-	pub const S2C_FOR_RECONNECT: u8 = 218;
-	pub const S2C_LOADING_SCREEN: u8 = 222;
+	packet_ids! {
+		C2S_TELEPORT = 1;
+		C2S_CLAIM_LOGIN_REWARD_MSG = 3;
+		C2S_REQUESTTRADE = 5;
+		C2S_JOINGUILD = 7;
+		C2S_PLAYERTEXT = 9;
+		C2S_USEITEM = 13;
+		C2S_GUILDREMOVE = 15;
+		C2S_PETUPGRADEREQUEST = 16;
+		C2S_INVDROP = 19;
+		C2S_OTHERHIT = 20;
+		C2S_ACTIVE_PET_UPDATE_REQUEST = 24;
+		C2S_ENEMYHIT = 25;
+		C2S_EDITACCOUNTLIST = 27;
+		C2S_PLAYERSHOOT = 30;
+		C2S_PONG = 31;
+		C2S_PET_CHANGE_SKIN_MSG = 33;
+		C2S_ACCEPTTRADE = 36;
+		C2S_CHANGEGUILDRANK = 37;
+		C2S_SQUAREHIT = 40;
+		C2S_USEPORTAL = 47;
+		C2S_QUEST_ROOM_MSG = 48;
+		C2S_RESKIN = 51;
+		C2S_RESET_DAILY_QUESTS = 52;
+		C2S_PET_CHANGE_FORM_MSG = 53;
+		C2S_INVSWAP = 55;
+		C2S_CHANGETRADE = 56;
+		C2S_CREATE = 57;
+		C2S_QUEST_REDEEM = 58;
+		C2S_CREATEGUILD = 59;
+		C2S_SETCONDITION = 60;
+		C2S_LOAD = 61;
+		C2S_MOVE = 62;
+		C2S_GOTOACK = 65;
+		C2S_HELLO = 74;
+		C2S_UPDATEACK = 81;
+		C2S_BUY = 85;
+		C2S_AOEACK = 89;
+		C2S_PLAYERHIT = 90;
+		C2S_CANCELTRADE = 91;
+		C2S_KEY_INFO_REQUEST = 94;
+		C2S_CHOOSENAME = 97;
+		C2S_QUEST_FETCH_ASK = 98;
+		C2S_CHECKCREDITS = 102;
+		C2S_GROUNDDAMAGE = 103;
+		C2S_GUILDINVITE = 104;
+		C2S_ESCAPE = 105;
+		C2S_QUEUE_CANCEL = 113;
+		C2S_REDEEM_EXALTATION_REWARD = 115;
+		C2S_FORGE_REQUEST = 118;
+		C2S_SHOOT_ACK = 121;
+		C2S_CHANGE_ALLYSHOOT = 122;
+		C2S_GET_PLAYERS_LIST_MESSAGE = 123;
+		C2S_MODERATOR_ACTION_MESSAGE = 124;
+		C2S_CREEP_MOVE_MESSAGE = 126;
+		C2S_CUSTOM_MAP_DELETE = 129;
+		C2S_CUSTOM_MAP_LIST = 131;
+		C2S_CREEP_HIT = 133;
+		C2S_PLAYER_CALLOUT = 134;
+		C2S_BUY_REFINEMENT = 136;
+		C2S_DASH = 137;
+		C2S_DASH_ACK = 138;
+		C2S_BUY_CUSTOMISATION_SOCKET = 140;
+		C2S_FAVOUR_PET = 145;
+		C2S_SKIN_RECYCLE = 146;
+		C2S_CLAIM_BATTLE_PASS = 149;
+		C2S_BOOST_BP_MILESTONE = 151;
+		C2S_CONVERT_SEASONAL_CHARACTER = 154;
+		C2S_RETITLE = 155;
+		C2S_SET_GRAVE_STONE = 156;
+		C2S_SET_ABILITY = 157;
+		C2S_EMOTE = 159;
+		C2S_BUY_EMOTE = 160;
+		C2S_SET_TRACKED_SEASON = 162;
+		C2S_CLAIM_MISSION = 163;
+		C2S_SET_DISCOVERABLE = 167;
+		C2S_UNLOCK_ENCHANTMENT_SLOT = 173;
+		C2S_UNLOCK_ENCHANTMENT = 175;
+		C2S_APPLY_ENCHANTMENT = 177;
+		C2S_ACTIVATE_CRUCIBLE = 180;
+		C2S_CRUCIBLE_REQUEST = 182;
+		C2S_UPGRADE_ENCHANTER = 185;
+		C2S_UPGRADE_ENCHANTMENT = 187;
+		C2S_REROLL_ALL_ENCHANTMENTS = 189;
+		C2S_RESET_ENCHANTMENT_REROLL_COUNT = 191;
+		C2S_CREATE_PARTY_MESSAGE = 200;
+		C2S_PARTY_ACTION = 207;
+		C2S_PARTY_INVITE_RESPONSE = 209;
+		//
+		//
+		S2C_FAILURE = 0;
+		S2C_DELETE_PET = 4;
+		S2C_QUEST_FETCH_RESPONSE = 6;
+		S2C_PING = 8;
+		S2C_NEWTICK = 10;
+		S2C_SHOWEFFECT = 11;
+		S2C_SERVERPLAYERSHOOT = 12;
+		S2C_TRADEACCEPTED = 14;
+		S2C_GOTO = 18;
+		S2C_NAMERESULT = 21;
+		S2C_BUYRESULT = 22;
+		S2C_HATCH_PET = 23;
+		S2C_GUILDRESULT = 26;
+		S2C_TRADECHANGED = 28;
+		S2C_TRADEDONE = 34;
+		S2C_ENEMYSHOOT = 35;
+		S2C_PLAYSOUND = 38;
+		S2C_VERIFY_EMAIL = 39;
+		S2C_NEW_ABILITY = 41;
+		S2C_UPDATE = 42;
+		S2C_TEXT = 44;
+		S2C_RECONNECT = 45;
+		S2C_DEATH = 46;
+		S2C_ALLYSHOOT = 49;
+		S2C_KEY_INFO_RESPONSE = 63;
+		S2C_AOE = 64;
+		S2C_GLOBAL_NOTIFICATION = 66;
+		S2C_NOTIFICATION = 67;
+		S2C_CLIENTSTAT = 69;
+		S2C_DAMAGE = 75;
+		S2C_ACTIVEPETUPDATE = 76;
+		S2C_INVITEDTOGUILD = 77;
+		S2C_PETYARDUPDATE = 78;
+		S2C_PASSWORD_PROMPT = 79;
+		S2C_QUESTOBJID = 82;
+		S2C_PIC = 83;
+		S2C_REALM_HERO_LEFT_MSG = 84;
+		S2C_TRADESTART = 86;
+		S2C_EVOLVE_PET = 87;
+		S2C_TRADEREQUESTED = 88;
+		S2C_MAPINFO = 92;
+		S2C_LOGIN_REWARD_MSG = 93;
+		S2C_INVRESULT = 95;
+		S2C_QUEST_REDEEM_RESPONSE = 96;
+		S2C_ACCOUNTLIST = 99;
+		S2C_CREATE_SUCCESS = 101;
+		S2C_FILE = 106;
+		S2C_RESKIN_UNLOCK = 107;
+		S2C_NEW_CHARACTER_INFORMATION = 108;
+		S2C_UNLOCK_INFORMATION = 109;
+		S2C_QUEUE_INFORMATION = 112;
+		S2C_EXALTATION_BONUS_CHANGED = 114;
+		S2C_VAULT_UPDATE = 117;
+		S2C_FORGE_RESULT = 119;
+		S2C_FORGE_UNLOCKED_BLUEPRINTS = 120;
+		S2C_STATS = 139;
+		S2C_UNKNOWN147 = 147;
+		S2C_DAMAGE_BOOST = 148;
+		S2C_CLAIM_BP_MILESTONE_RESULT = 150;
+		S2C_UNKNOWN164 = 164;
+		S2C_UNKNOWN165 = 165;
+		S2C_STASIS = 166;
+		S2C_REALM_SCORE_UPDATE = 169;
+		S2C_CLAIM_REWARDS_INFO_PROMPT = 170;
+		S2C_CLAIM_CHEST_REWARD = 171;
+		S2C_CHEST_REWARD_RESULT = 172;
+		S2C_UNKNOWN181 = 181;
+		S2C_CRUCIBLE_RESPONSE = 183;
+		S2C_UNKNOWN190 = 190;
+		S2C_PARTY_ACTION_RESULT = 204;
+		S2C_INCOMING_PARTY_INVITE = 208;
+		S2C_INCOMING_PARTY_MEMBER_INFO = 210;
+		S2C_PARTY_MEMBER_ADDED = 212;
+		S2C_PARTY_LIST_MESSAGE = 214;
+		S2C_PARTY_JOIN_REQUEST = 215;
+		S2C_PARTY_REQUEST_RESPONSE = 217;
+		S2C_FOR_RECONNECT = 218;
+		S2C_LOADING_SCREEN = 222;
+	}
+}
+
+/// Name of the packet without the direction prefix, if known
+pub fn packet_name(direction: Direction, id: u8) -> Option<&'static str> {
+	let prefix = match direction {
+		Direction::C2S => "C2S_",
+		Direction::S2C => "S2C_",
+	};
+
+	PACKET_ID::ALL
+		.iter()
+		.find(|&&(name, packet_id)| packet_id == id && name.starts_with(prefix))
+		.map(|(name, _)| &name[prefix.len()..])
 }
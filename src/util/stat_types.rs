@@ -141,32 +141,64 @@ pub mod STAT_TYPE {
 	pub const BLIZZARD: u8 = 150;
 }
 
+// The bit of a condition effect with id N is 1 << (N - 1) in CONDITION if N < 32,
+// and 1 << (N - 32) in CONDITION2 otherwise (see util::conditions)
 #[allow(dead_code, non_snake_case)]
 pub mod CONDITION_BITFLAG {
+	pub const DEAD: u64 = 0x1;
+	pub const QUIET: u64 = 0x2;
 	pub const WEAK: u64 = 0x4;
 	pub const SLOW: u64 = 0x8;
 	pub const SICK: u64 = 0x10;
+	pub const DAZED: u64 = 0x20;
+	pub const STUNNED: u64 = 0x40;
 	pub const BLIND: u64 = 0x80;
 	pub const HALLUCINATING: u64 = 0x1_00;
 	pub const DRUNK: u64 = 0x2_00;
 	pub const CONFUSED: u64 = 0x4_00;
+	pub const STUN_IMMUNE: u64 = 0x8_00;
+	pub const INVISIBLE: u64 = 0x10_00;
+	pub const PARALYZED: u64 = 0x20_00;
+	pub const SPEEDY: u64 = 0x40_00;
 	pub const BLEEDING: u64 = 0x80_00;
+	pub const ARMOR_BROKEN_IMMUNE: u64 = 0x1_00_00;
 	pub const HEALING: u64 = 0x2_00_00;
 	pub const DAMAGING: u64 = 0x4_00_00;
+	pub const BERSERK: u64 = 0x8_00_00;
 	pub const IN_COMBAT: u64 = 0x10_00_00;
 	pub const STASIS: u64 = 0x20_00_00;
+	pub const STASIS_IMMUNE: u64 = 0x40_00_00;
 	pub const INVINCIBLE: u64 = 0x80_00_00;
 	pub const INVULNERABLE: u64 = 0x1_00_00_00;
 	pub const ARMORED: u64 = 0x2_00_00_00;
 	pub const ARMOR_BROKEN: u64 = 0x4_00_00_00;
 	pub const HEXED: u64 = 0x8_00_00_00;
+	pub const NINJA_SPEEDY: u64 = 0x10_00_00_00;
 	pub const UNSTABLE: u64 = 0x20_00_00_00;
 	pub const DARKNESS: u64 = 0x40_00_00_00;
 }
 
 #[allow(dead_code, non_snake_case)]
 pub mod CONDITION2_BITFLAG {
+	pub const SLOW_IMMUNE: u64 = 0x1;
+	pub const DAZED_IMMUNE: u64 = 0x2;
+	pub const PARALYZED_IMMUNE: u64 = 0x4;
 	pub const PETRIFIED: u64 = 0x8;
+	pub const PETRIFIED_IMMUNE: u64 = 0x10;
+	pub const PET_EFFECT_ICON: u64 = 0x20;
 	pub const CURSED: u64 = 0x40;
+	pub const CURSE_IMMUNE: u64 = 0x80;
+	pub const HP_BOOST: u64 = 0x1_00;
+	pub const MP_BOOST: u64 = 0x2_00;
+	pub const ATT_BOOST: u64 = 0x4_00;
+	pub const DEF_BOOST: u64 = 0x8_00;
+	pub const SPD_BOOST: u64 = 0x10_00;
+	pub const VIT_BOOST: u64 = 0x20_00;
+	pub const WIS_BOOST: u64 = 0x40_00;
+	pub const DEX_BOOST: u64 = 0x80_00;
+	pub const SILENCED: u64 = 0x1_00_00;
 	pub const EXPOSED: u64 = 0x2_00_00;
+	pub const ENERGIZED: u64 = 0x4_00_00;
+	pub const DROUGHT: u64 = 0x20_00_00_00;
+	pub const LETHAL_STRIKE: u64 = 0x40_00_00_00;
 }